@@ -0,0 +1,51 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion};
+use rand::rngs::SmallRng;
+use rand::{Rng as _, SeedableRng as _};
+use rustc_hash::FxBuildHasher;
+use small_type_id::TypeId;
+use small_type_id::hash::BuildTypeIdHasher;
+
+const SIZES: &[usize] = &[16, 1_000, 100_000];
+
+/// Generates ids similar to ones generated by derive macro.
+fn generate_ids(len: usize) -> Vec<TypeId> {
+    let mut rng = SmallRng::seed_from_u64(len as u64);
+    (0..len)
+        .map(|_| TypeId::from_u32(rng.random_range(1..=0x7FFF_FFFF)).unwrap())
+        .collect()
+}
+
+fn bench_lookup<S: BuildHasher + Default>(
+    g: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    name: &str,
+    ids: &[TypeId],
+) {
+    let map: hashbrown::HashMap<TypeId, usize, S> =
+        ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    g.bench_with_input(BenchmarkId::new(name, ids.len()), ids, |b, ids| {
+        b.iter(|| {
+            let mut sum = 0usize;
+            for id in black_box(ids) {
+                sum = sum.wrapping_add(map[id]);
+            }
+            sum
+        });
+    });
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut g = c.benchmark_group("hash_map_lookup");
+    for &len in SIZES {
+        let ids = generate_ids(len);
+        bench_lookup::<RandomState>(&mut g, "sip_hash", &ids);
+        bench_lookup::<FxBuildHasher>(&mut g, "fx_hash", &ids);
+        bench_lookup::<BuildTypeIdHasher>(&mut g, "type_id_hasher", &ids);
+    }
+}
+
+criterion::criterion_group!(benches, criterion_benchmark);
+criterion::criterion_main!(benches);
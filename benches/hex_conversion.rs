@@ -50,7 +50,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         fn prepare_buffer() -> String {
             String::with_capacity(16)
         }
-        let mut g = c.benchmark_group(&format!("stream {:X}", num));
+        let mut g = c.benchmark_group(format!("stream {:X}", num));
         g.bench_function("current_version", |b| {
             b.iter_batched(
                 prepare_buffer,
@@ -99,7 +99,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         });
     }
     for &num in NUMS_TO_TEST {
-        let mut g = c.benchmark_group(&format!("to_string {:X}", num));
+        let mut g = c.benchmark_group(format!("to_string {:X}", num));
         g.bench_function("current_version", |b| {
             b.iter(|| {
                 let num = black_box(num);
@@ -127,6 +127,31 @@ fn criterion_benchmark(c: &mut Criterion) {
             });
         });
     }
+    for &num in NUMS_TO_TEST {
+        let mut g = c.benchmark_group(format!("lower {:X}", num));
+        g.bench_function("current_version", |b| {
+            b.iter(|| {
+                let num = black_box(num);
+                hex::HexView::new_lower(num).as_str().to_string()
+            });
+        });
+        g.bench_function("std_hex_fmt", |b| {
+            b.iter(|| {
+                let num = black_box(num);
+                format!("{:x}", num)
+            });
+        });
+    }
+    for &num in NUMS_TO_TEST {
+        let text = format!("{:X}", num);
+        let mut g = c.benchmark_group(format!("parse {:X}", num));
+        g.bench_function("current_version", |b| {
+            b.iter(|| hex::parse(black_box(text.as_bytes())));
+        });
+        g.bench_function("std_from_str_radix", |b| {
+            b.iter(|| u32::from_str_radix(black_box(&text), 16).ok());
+        });
+    }
 }
 
 criterion::criterion_group!(benches, criterion_benchmark);
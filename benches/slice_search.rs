@@ -0,0 +1,120 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion};
+use rand::rngs::SmallRng;
+use rand::{Rng as _, SeedableRng as _};
+use small_type_id::{TypeId, slice};
+
+const SIZES: &[usize] = &[4, 16, 64, 256];
+
+/// Generates unique sorted ids similar to ones generated by derive macro.
+fn generate_ids(len: usize, seed: u64) -> Vec<TypeId> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut ids = Vec::with_capacity(len);
+    while ids.len() < len {
+        ids.push(TypeId::from_u32(rng.random_range(1..=0x7FFF_FFFF)).unwrap());
+        ids.sort_unstable();
+        ids.dedup();
+    }
+    ids
+}
+
+/// Returns ids to search: every id of `ids` and same number of missing ones.
+fn needles(ids: &[TypeId]) -> Vec<TypeId> {
+    let missing = generate_ids(ids.len(), u64::MAX);
+    ids.iter().chain(&missing).copied().collect()
+}
+
+fn scalar_is_subset_sorted(sub: &[TypeId], sup: &[TypeId]) -> bool {
+    let mut rest = sup;
+    for id in sub {
+        match rest.binary_search(id) {
+            Ok(i) => rest = &rest[i + 1..],
+            Err(_) => return false,
+        }
+    }
+    true
+}
+
+fn scalar_intersect_sorted<'o>(
+    a: &[TypeId],
+    b: &[TypeId],
+    out: &'o mut [TypeId],
+) -> &'o mut [TypeId] {
+    let (mut i, mut j, mut len) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                out[len] = a[i];
+                len += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    &mut out[..len]
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut g = c.benchmark_group("slice_contains");
+    for &len in SIZES {
+        let ids = generate_ids(len, len as u64);
+        let needles = needles(&ids);
+        g.bench_with_input(BenchmarkId::new("scalar", len), &ids, |b, ids| {
+            b.iter(|| {
+                needles
+                    .iter()
+                    .filter(|id| black_box(ids).contains(id))
+                    .count()
+            });
+        });
+        g.bench_with_input(BenchmarkId::new("small_type_id", len), &ids, |b, ids| {
+            b.iter(|| {
+                needles
+                    .iter()
+                    .filter(|&&id| slice::contains(black_box(ids), id))
+                    .count()
+            });
+        });
+    }
+    g.finish();
+
+    let mut g = c.benchmark_group("slice_is_subset_sorted");
+    for &len in SIZES {
+        let sup = generate_ids(len, len as u64);
+        // Every fourth element so lookup has to skip some of them.
+        let sub: Vec<TypeId> = sup.iter().step_by(4).copied().collect();
+        g.bench_with_input(BenchmarkId::new("scalar", len), &sup, |b, sup| {
+            b.iter(|| scalar_is_subset_sorted(black_box(&sub), black_box(sup)));
+        });
+        g.bench_with_input(BenchmarkId::new("small_type_id", len), &sup, |b, sup| {
+            b.iter(|| slice::is_subset_sorted(black_box(&sub), black_box(sup)));
+        });
+    }
+    g.finish();
+
+    // Half of elements are common in dense case, and every eighth element of longer slice in skewed case.
+    for (name, short_len) in [("dense", 1), ("skewed", 8)] {
+        let mut g = c.benchmark_group(format!("slice_intersect_sorted_{name}"));
+        for &len in SIZES {
+            let a = generate_ids(len, len as u64);
+            let mut b_ids: Vec<TypeId> = a.iter().step_by(2 * short_len).copied().collect();
+            b_ids.extend(generate_ids(len / 2 / short_len, u64::MAX));
+            b_ids.sort_unstable();
+            b_ids.dedup();
+            let mut out = vec![a[0]; len];
+            g.bench_with_input(BenchmarkId::new("scalar", len), &a, |b, a| {
+                b.iter(|| scalar_intersect_sorted(black_box(a), black_box(&b_ids), &mut out).len());
+            });
+            g.bench_with_input(BenchmarkId::new("small_type_id", len), &a, |b, a| {
+                b.iter(|| slice::intersect_sorted(black_box(a), black_box(&b_ids), &mut out).len());
+            });
+        }
+        g.finish();
+    }
+}
+
+criterion::criterion_group!(benches, criterion_benchmark);
+criterion::criterion_main!(benches);
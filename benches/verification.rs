@@ -0,0 +1,60 @@
+use std::hint::black_box;
+
+use criterion::BatchSize::LargeInput;
+use criterion::{BenchmarkId, Criterion};
+use rand::rngs::SmallRng;
+use rand::{Rng as _, SeedableRng as _};
+
+#[allow(dead_code, unused_imports)]
+#[path = "../small_type_id/src/duplicates.rs"]
+mod duplicates;
+
+const SIZES: &[usize] = &[10_000, 100_000, 1_000_000];
+
+/// Generates unique ids similar to ones generated by derive macro.
+fn generate_ids(len: usize) -> Vec<u32> {
+    let mut rng = SmallRng::seed_from_u64(len as u64);
+    let mut ids: Vec<u32> = (0..len)
+        .map(|_| rng.random_range(1..=0x7FFF_FFFF))
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    while ids.len() < len {
+        ids.push(rng.random_range(1..=0x7FFF_FFFF));
+        ids.sort_unstable();
+        ids.dedup();
+    }
+    // Shuffle back to order of registration.
+    for i in (1..ids.len()).rev() {
+        ids.swap(i, rng.random_range(0..=i));
+    }
+    ids
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut g = c.benchmark_group("verification");
+    g.sample_size(10);
+    for &len in SIZES {
+        let ids = generate_ids(len);
+        g.bench_with_input(BenchmarkId::new("sort_in_place", len), &ids, |b, ids| {
+            b.iter_batched(
+                || ids.clone(),
+                |mut ids| {
+                    ids.sort_unstable();
+                    duplicates::has_sorted_duplicates(core::iter::once(black_box(&ids[..])))
+                },
+                LargeInput,
+            );
+        });
+        g.bench_with_input(BenchmarkId::new("chunked", len), &ids, |b, ids| {
+            b.iter(|| {
+                duplicates::ChunkedDuplicates::new(black_box(ids).iter().copied())
+                    .next()
+                    .is_some()
+            });
+        });
+    }
+}
+
+criterion::criterion_group!(benches, criterion_benchmark);
+criterion::criterion_main!(benches);
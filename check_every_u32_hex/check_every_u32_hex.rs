@@ -65,6 +65,17 @@ fn check_range(start: u32, end: u32, counter: &AtomicU64) {
         let hs = hx.as_str();
 
         assert_eq!(s, hs, "Hex views not match for {}", v);
+        assert_eq!(
+            hex::parse(hs.as_bytes()),
+            Some(v),
+            "Parsing failed for {}",
+            v
+        );
+
+        s.clear();
+        write!(&mut s, "{:x}", v).unwrap();
+        let hx = hex::HexView::new_lower(v);
+        assert_eq!(s, hx.as_str(), "Lowercase hex views not match for {}", v);
 
         i += 1;
         report_counter += 1;
@@ -16,6 +16,10 @@ class TestSet:
     ret_code: int
     stdout: str
     stderr: str
+    env: tuple[tuple[str, str], ...] = ()
+    rustflags: str = ""
+    # Only checks that every line of stderr is present in etalon.
+    partial_stderr: bool = False
 
 def lookup_clang_asan():
     assert platform.system() == "Windows"
@@ -91,13 +95,25 @@ def run_test(params: TestSet, is_release: bool, lto: str, use_asan: bool, target
             print("Running without lto")
         print(f"    > {build_args}")
         print(build_args.split())
-        subprocess.run(build_args.split(), check=True)
+        build_env = dict(os.environ)
+        if params.rustflags:
+            build_env["RUSTFLAGS"] = (build_env.get("RUSTFLAGS", "") + " " + params.rustflags).strip()
+            print(f"    RUSTFLAGS={build_env['RUSTFLAGS']}")
+        subprocess.run(build_args.split(), check=True, env=build_env)
         executable = f"../target/{target}/{mod_str}/duplicate_type_ids_handling"
         print(f"Running\n    {executable}")
-        run_res = subprocess.run(executable, capture_output=True)
+        env = dict(os.environ)
+        env.update(params.env)
+        run_res = subprocess.run(executable, capture_output=True, env=env)
         assert run_res.returncode == params.ret_code, f"Return code doesn't match: {run_res.returncode} != {params.ret_code}"
         assert run_res.stdout == out, f"stdout doesn't match: {repr(out)} != {repr(params.stdout)}"
-        assert run_res.stderr == err, f"stderr doesn't match: {repr(err)} != {repr(params.stderr)}"
+        if params.partial_stderr:
+            expected_lines = set(err.splitlines())
+            assert run_res.stderr, "stderr is empty"
+            for line in run_res.stderr.splitlines():
+                assert line in expected_lines, f"unexpected line in stderr: {repr(line)}"
+        else:
+            assert run_res.stderr == err, f"stderr doesn't match: {repr(err)} != {repr(params.stderr)}"
     finally:
         if lto:
             shutil.move(workspace_cargo + ".orig", workspace_cargo)
@@ -129,8 +145,72 @@ tests = (
         stdout="etalons/m_stdout_no_names.txt", stderr="etalons/m_stderr.txt"),
     TestSet(features=fs({"unsafe_remove_duplicate_checks", "debug_type_name"}),
         ret_code=0, stdout="etalons/m_stdout_with_names.txt", stderr="etalons/m_stderr.txt"),
+    # Error of `verify_unique` saves duplicates.
+    TestSet(features=fs({"unsafe_remove_duplicate_checks", "debug_type_name", "alloc"}),
+        ret_code=0, stdout="etalons/m_stdout_with_names.txt", stderr="etalons/m_stderr.txt"),
+    TestSet(features=fs({"warn_on_collisions"}), ret_code=0,
+        stdout="etalons/m_stdout_no_names.txt", stderr="etalons/warn_no_names.txt"),
+    TestSet(features=fs({"warn_on_collisions", "debug_type_name"}), ret_code=0,
+        stdout="etalons/m_stdout_with_names.txt", stderr="etalons/warn_with_names.txt"),
+    TestSet(features=fs({"hook_on_collisions"}), ret_code=3,
+        stdout="etalons/hook_stdout.txt", stderr=""),
+    # Verification runs when main calls `iter_registered_types`.
+    TestSet(features=fs({"lazy_verification"}), ret_code=error_code, stdout="", stderr="etalons/auto_no_names.txt"),
+    TestSet(features=fs({"lazy_verification", "debug_type_name"}), ret_code=error_code,
+        stdout="", stderr="etalons/auto_with_names.txt"),
+    # Verification runs when main downcasts value.
+    TestSet(features=fs({"lazy_verification", "downcast_first"}), ret_code=error_code,
+        stdout="", stderr="etalons/auto_no_names.txt"),
+    # Verification runs when main pushes value into `CommandBuffer`.
+    TestSet(features=fs({"lazy_verification", "push_first"}), ret_code=error_code,
+        stdout="", stderr="etalons/auto_no_names.txt"),
+    TestSet(features=fs({"manual_registry", "downcast_first"}), ret_code=error_code,
+        stdout="", stderr="etalons/auto_no_names.txt"),
+    TestSet(features=fs({"manual_registry"}), ret_code=error_code, stdout="", stderr="etalons/auto_no_names.txt"),
+    TestSet(features=fs({"manual_registry", "debug_type_name"}), ret_code=error_code,
+        stdout="", stderr="etalons/auto_with_names.txt"),
+    TestSet(features=fs({"manual_registry", "unsafe_remove_duplicate_checks"}), ret_code=0,
+        stdout="etalons/m_stdout_no_names.txt", stderr="etalons/m_stderr.txt"),
+    # Process is terminated by constructor that finds duplicate
+    # and order of constructors is unspecified so only some duplicates are reported.
+    # Names are not checked because types reported for same id depend on order too.
+    TestSet(features=fs({"force_ctor_registration"}), ret_code=error_code,
+        stdout="", stderr="etalons/auto_no_names.txt", partial_stderr=True),
+    # Order of constructors is unspecified so only checks that all types are registered.
+    TestSet(features=fs({"force_ctor_registration", "unsafe_remove_duplicate_checks"}), ret_code=0,
+        stdout="etalons/m_stdout_no_names.txt", stderr="etalons/m_stderr.txt"),
+    TestSet(features=fs({"force_ctor_registration", "unsafe_remove_duplicate_checks", "debug_type_name"}),
+        ret_code=0, stdout="etalons/m_stdout_with_names.txt", stderr="etalons/m_stderr.txt"),
+    # JSON report is redirected to stdout to keep it apart from human readable one.
+    TestSet(features=fs({"json_report"}), ret_code=error_code,
+        stdout="etalons/json_no_names.txt", stderr="etalons/auto_no_names.txt",
+        env=(("SMALL_TYPE_ID_JSON_REPORT_FD", "1"),)),
+    TestSet(features=fs({"json_report", "debug_type_name"}), ret_code=error_code,
+        stdout="etalons/json_with_names.txt", stderr="etalons/auto_with_names.txt",
+        env=(("SMALL_TYPE_ID_JSON_REPORT_FD", "1"),)),
 )
 
+if platform.system() == "Linux":
+    linker_script_flags = "-C link-arg=-T" + os.path.abspath("../small_type_id/small_type_id.ld")
+    tests += (
+        TestSet(features=fs({"linux_raw_syscalls"}), ret_code=error_code,
+            stdout="", stderr="etalons/auto_no_names.txt"),
+        TestSet(features=fs({"linux_raw_syscalls", "warn_on_collisions"}), ret_code=0,
+            stdout="etalons/m_stdout_no_names.txt", stderr="etalons/warn_no_names.txt"),
+        TestSet(features=fs({"linux_raw_syscalls", "json_report", "debug_type_name"}), ret_code=error_code,
+            stdout="etalons/json_with_names.txt", stderr="etalons/auto_with_names.txt",
+            env=(("SMALL_TYPE_ID_JSON_REPORT_FD", "1"),)),
+        # Verification runs before main using constructor.
+        TestSet(features=fs({"linker_script_registration"}), ret_code=error_code,
+            stdout="", stderr="etalons/auto_no_names.txt", rustflags=linker_script_flags),
+        TestSet(features=fs({"linker_script_registration", "debug_type_name"}), ret_code=error_code,
+            stdout="", stderr="etalons/auto_with_names.txt", rustflags=linker_script_flags),
+        TestSet(features=fs({"linker_script_registration", "downcast_first"}), ret_code=error_code,
+            stdout="", stderr="etalons/auto_no_names.txt", rustflags=linker_script_flags),
+        TestSet(features=fs({"linker_script_registration", "unsafe_remove_duplicate_checks"}), ret_code=0,
+            stdout="etalons/m_stdout_no_names.txt", stderr="etalons/m_stderr.txt", rustflags=linker_script_flags),
+    )
+
 parser = argparse.ArgumentParser()
 parser.add_argument("--use-asan", action='store_true')
 args = parser.parse_args()
@@ -23,26 +23,91 @@ pub enum Jaaadtd {
     B,
 }
 
+#[derive(small_type_id::HasTypeId)]
+pub struct TMdfjD;
+
+#[derive(small_type_id::HasTypeId)]
+pub struct TylSgT;
+
+#[derive(small_type_id::HasTypeId)]
+pub struct TTzwrv;
+
 const _: () = {
     assert!(XaaG::TYPE_ID.as_u32() == Jaaadtd::TYPE_ID.as_u32());
+    assert!(TMdfjD::TYPE_ID.as_u32() == TylSgT::TYPE_ID.as_u32());
+    assert!(TMdfjD::TYPE_ID.as_u32() == TTzwrv::TYPE_ID.as_u32());
 };
 
+#[cfg(feature = "manual_registry")]
+small_type_id::registry! { XaaG, Jaaadtd, TMdfjD, TylSgT, TTzwrv }
+
+#[cfg(feature = "warn_on_collisions")]
+small_type_id::collision_policy!(unsafe { Warn });
+
+#[cfg(feature = "hook_on_collisions")]
+small_type_id::collision_policy!(unsafe { Hook(exit_on_collisions) });
+
+#[cfg(feature = "hook_on_collisions")]
+fn exit_on_collisions(report: &small_type_id::CollisionReport) {
+    for collision in report.collisions() {
+        println!(
+            "Hook found {} types with type_id {}",
+            collision.types().count(),
+            collision.type_id()
+        );
+    }
+    std::process::exit(3);
+}
+
 fn main() {
+    #[cfg(feature = "downcast_first")]
+    {
+        // Ids are same so downcast would succeed if it didn't verify them first.
+        let value: &dyn small_type_id::any::SmallAny = &XaaG { f: 1 };
+        assert!(value.downcast_ref::<Jaaadtd>().is_none());
+    }
+    #[cfg(feature = "push_first")]
+    {
+        // Ids are same so second value would be dropped as first type if it didn't verify them first.
+        let mut buffer = small_type_id::command_buffer::CommandBuffer::new();
+        buffer.push(XaaG { f: 1 });
+        buffer.push(Jaaadtd::A);
+        // Must not be reached because verification terminates process.
+        println!("Pushed {} values", buffer.len());
+    }
     let mut set = HashSet::new();
     let mut tested = 0;
+    let mut duplicates = Vec::new();
     #[cfg(feature = "debug_type_name")]
     let mut names = Vec::new();
     for entry in small_type_id::iter_registered_types() {
         if !set.insert(entry.type_id) {
-            eprintln!(
-                "Detected error at the start of main! Found duplicate type_id {}.",
-                entry.type_id
-            );
+            duplicates.push(entry.type_id);
         }
         tested += 1;
         #[cfg(feature = "debug_type_name")]
         names.push(entry.debug_type_name);
     }
+    // Order of entries is not specified so we sort them for ease of testing.
+    duplicates.sort_unstable();
+    duplicates.dedup();
+    for type_id in duplicates {
+        eprintln!(
+            "Detected error at the start of main! Found duplicate type_id {}.",
+            type_id
+        );
+    }
+    if let Err(err) = small_type_id::verify_unique() {
+        #[cfg(feature = "alloc")]
+        for ((type_id, types), collision) in err.groups().zip(err.collisions()) {
+            assert_eq!(type_id, collision.type_id());
+            assert!(types.len() > 1);
+            assert!(types.iter().all(|t| t.type_id == type_id));
+        }
+        let mut reported: Vec<_> = err.collisions().map(|c| c.type_id()).collect();
+        reported.sort_unstable();
+        eprintln!("verify_unique reported duplicates {:?}.", reported);
+    }
     println!("Tested {} entries, found {} types", tested, set.len());
     #[cfg(feature = "debug_type_name")]
     {
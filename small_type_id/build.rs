@@ -0,0 +1,19 @@
+//! Selects the way types are registered, so the choice is made in a single place.
+
+use std::env;
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(small_type_id_link_section)");
+    println!("cargo::rerun-if-changed=build.rs");
+
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    let feature = |name: &str| env::var_os(format!("CARGO_FEATURE_{name}")).is_some();
+
+    // Entries are put into link sections where we know how to find them,
+    // otherwise every entry is registered by its own constructor.
+    if (target_os == "windows" || target_os == "linux" || feature("LINKER_SCRIPT_REGISTRATION"))
+        && !feature("FORCE_CTOR_REGISTRATION")
+    {
+        println!("cargo::rustc-cfg=small_type_id_link_section");
+    }
+}
@@ -0,0 +1,263 @@
+//! Dynamic typing using [`TypeId`].
+//!
+//! [`SmallAny`] is a replacement of [`core::any::Any`] for types that implement [`HasTypeId`].
+//! Unlike [`core::any::Any`], it uses 32 bit [`TypeId`] and its only source of safety
+//! is the contract of [`HasTypeId`] trait.
+//!
+//! ```
+//! use small_type_id::any::SmallAny;
+//!
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Meters(f32);
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Seconds(f32);
+//!
+//! let values: [&dyn SmallAny; 2] = [&Meters(3.0), &Seconds(1.5)];
+//! assert!(values[0].is::<Meters>());
+//! assert_eq!(values[1].downcast_ref::<Seconds>().map(|x| x.0), Some(1.5));
+//! assert!(values[1].downcast_ref::<Meters>().is_none());
+//! ```
+//!
+//! If feature [`alloc`](crate#feature-alloc) is enabled, [`Downcast`] trait allows
+//! downcasting of `Box`, `Rc` and `Arc` of `dyn SmallAny`, `dyn SmallAny + Send`
+//! and `dyn SmallAny + Send + Sync`.
+//!
+//! ```
+//! # #[cfg(feature = "alloc")]
+//! # {
+//! use std::sync::Arc;
+//!
+//! use small_type_id::any::{Downcast as _, SmallAny};
+//!
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Message(&'static str);
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Signal;
+//!
+//! let boxed: Box<dyn SmallAny + Send> = Box::new(Message("hi"));
+//! let message: Box<Message> = boxed.downcast().ok().unwrap();
+//! assert_eq!(message.0, "hi");
+//!
+//! let shared: Arc<dyn SmallAny + Send + Sync> = Arc::new(Message("hello"));
+//! let shared = shared.downcast::<Signal>().err().unwrap();
+//! assert!(shared.downcast::<Message>().is_ok());
+//! # }
+//! ```
+//!
+//! [`ThinAny`] is an owning pointer to value of any type that takes only one pointer,
+//! unlike `Box<dyn SmallAny>`. [`ThinAnySend`] holds only values that implement [`Send`]
+//! and can be sent between threads.
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::rc::Rc;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+use alloc::sync::Arc;
+
+#[cfg(feature = "alloc")]
+pub use crate::thin_any::{ThinAny, ThinAnySend};
+use crate::{HasTypeId, TypeId};
+
+/// Object safe trait that allows downcasting of trait objects.
+///
+/// Implemented for every type that implements [`HasTypeId`] and cannot be implemented manually:
+///
+/// ```compile_fail
+/// struct Liar;
+///
+/// impl small_type_id::any::SmallAny for Liar {
+///     fn small_type_id(&self) -> small_type_id::TypeId {
+///         small_type_id::TypeId::from_u32(1).unwrap()
+///     }
+/// }
+/// ```
+pub trait SmallAny: sealed::Sealed + 'static {
+    /// Returns [`TypeId`] of `self`.
+    fn small_type_id(&self) -> TypeId;
+}
+
+impl<T: HasTypeId> SmallAny for T {
+    #[inline]
+    fn small_type_id(&self) -> TypeId {
+        T::TYPE_ID
+    }
+}
+
+mod sealed {
+    /// Prevents implementations of [`SmallAny`](super::SmallAny) that return wrong [`TypeId`].
+    pub trait Sealed {}
+
+    impl<T: crate::HasTypeId> Sealed for T {}
+}
+
+/// Downcasting of smart pointers to [`SmallAny`] trait objects.
+///
+/// Available only if feature [`alloc`](crate#feature-alloc) is enabled.
+/// Implemented for `Box`, `Rc` and `Arc` of `dyn SmallAny`, `dyn SmallAny + Send`
+/// and `dyn SmallAny + Send + Sync`.
+#[cfg(feature = "alloc")]
+pub trait Downcast: Sized + sealed::Sealed {
+    /// Pointer to value of type `T` of the same kind as `Self`.
+    type Output<T: HasTypeId>;
+
+    /// Converts pointer to pointer to `T` if value has type `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` back if value has different type.
+    fn downcast<T: HasTypeId>(self) -> Result<Self::Output<T>, Self>;
+}
+
+impl dyn SmallAny {
+    /// Returns `true` if value has type `T`.
+    ///
+    /// Calls [`ensure_verified`](crate::ensure_verified) first
+    /// because downcasts rely on uniqueness of [`TypeId`]s.
+    #[must_use]
+    #[inline]
+    pub fn is<T: HasTypeId>(&self) -> bool {
+        crate::implementation::ensure_verified();
+        self.small_type_id() == T::TYPE_ID
+    }
+
+    /// Returns reference to value if it has type `T`.
+    #[must_use]
+    #[inline]
+    pub fn downcast_ref<T: HasTypeId>(&self) -> Option<&T> {
+        if self.is::<T>() {
+            // SAFETY: `SmallAny` is implemented only by types that implement `HasTypeId`,
+            // and `TypeId`s of such types are unique by contract of `HasTypeId`.
+            Some(unsafe { self.downcast_ref_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns mutable reference to value if it has type `T`.
+    #[must_use]
+    #[inline]
+    pub fn downcast_mut<T: HasTypeId>(&mut self) -> Option<&mut T> {
+        if self.is::<T>() {
+            // SAFETY: Same as in `downcast_ref`.
+            Some(unsafe { self.downcast_mut_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// # Safety
+    ///
+    /// Value must have type `T`.
+    #[inline]
+    pub(crate) unsafe fn downcast_ref_unchecked<T: HasTypeId>(&self) -> &T {
+        debug_assert!(self.is::<T>());
+        // SAFETY: Caller ensures that value has type `T`.
+        unsafe { &*core::ptr::from_ref(self).cast::<T>() }
+    }
+
+    /// # Safety
+    ///
+    /// Value must have type `T`.
+    #[inline]
+    pub(crate) unsafe fn downcast_mut_unchecked<T: HasTypeId>(&mut self) -> &mut T {
+        debug_assert!(self.is::<T>());
+        // SAFETY: Caller ensures that value has type `T`.
+        unsafe { &mut *core::ptr::from_mut(self).cast::<T>() }
+    }
+}
+
+// Trait objects with auto traits only forward to `dyn SmallAny`.
+macro_rules! forward_dyn_small_any {
+    ($($dyn_ty:ty),*) => {$(
+        impl $dyn_ty {
+            /// Same as `is` of `dyn SmallAny`.
+            #[must_use]
+            #[inline]
+            pub fn is<T: HasTypeId>(&self) -> bool {
+                <dyn SmallAny>::is::<T>(self)
+            }
+
+            /// Same as `downcast_ref` of `dyn SmallAny`.
+            #[must_use]
+            #[inline]
+            pub fn downcast_ref<T: HasTypeId>(&self) -> Option<&T> {
+                <dyn SmallAny>::downcast_ref::<T>(self)
+            }
+
+            /// Same as `downcast_mut` of `dyn SmallAny`.
+            #[must_use]
+            #[inline]
+            pub fn downcast_mut<T: HasTypeId>(&mut self) -> Option<&mut T> {
+                <dyn SmallAny>::downcast_mut::<T>(self)
+            }
+        }
+
+        impl core::fmt::Debug for $dyn_ty {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(self as &dyn SmallAny, f)
+            }
+        }
+    )*};
+}
+
+forward_dyn_small_any!(dyn SmallAny + Send, dyn SmallAny + Send + Sync);
+
+impl core::fmt::Debug for dyn SmallAny {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("SmallAny")
+            .field(&self.small_type_id())
+            .finish()
+    }
+}
+
+/// Implements [`Downcast`] for pointer to all variants of trait object.
+/// Pointer type must have `into_raw` and `from_raw` functions.
+#[cfg(feature = "alloc")]
+macro_rules! impl_downcast {
+    ($ptr:ident) => {
+        impl_downcast!($ptr, dyn SmallAny);
+        impl_downcast!($ptr, dyn SmallAny + Send);
+        impl_downcast!($ptr, dyn SmallAny + Send + Sync);
+    };
+    ($ptr:ident, $dyn_ty:ty) => {
+        impl sealed::Sealed for $ptr<$dyn_ty> {}
+
+        impl Downcast for $ptr<$dyn_ty> {
+            type Output<T: HasTypeId> = $ptr<T>;
+
+            #[inline]
+            fn downcast<T: HasTypeId>(self) -> Result<$ptr<T>, Self> {
+                if self.is::<T>() {
+                    let ptr = $ptr::into_raw(self).cast::<T>();
+                    // SAFETY: Value has type `T`, as checked above,
+                    // so it was allocated with layout of `T`.
+                    Ok(unsafe { $ptr::from_raw(ptr) })
+                } else {
+                    Err(self)
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "alloc")]
+impl_downcast!(Box);
+#[cfg(feature = "alloc")]
+impl_downcast!(Rc);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_downcast!(Arc);
+
+/// Converts box to type `T`.
+///
+/// # Safety
+///
+/// Value must have type `T`.
+#[cfg(feature = "alloc")]
+pub(crate) unsafe fn downcast_box_unchecked<T: HasTypeId>(b: Box<dyn SmallAny>) -> Box<T> {
+    debug_assert!(b.is::<T>());
+    let ptr = Box::into_raw(b).cast::<T>();
+    // SAFETY: Caller ensures that box contains value of type `T`
+    // so it was allocated with layout of `T`.
+    unsafe { Box::from_raw(ptr) }
+}
@@ -0,0 +1,24 @@
+//! Implementations of [`bytemuck`](::bytemuck) traits.
+
+use ::bytemuck::{CheckedBitPattern, NoUninit, ZeroableInOption};
+
+use crate::TypeId;
+
+// SAFETY: `TypeId` is `repr(transparent)` wrapper around `NonZeroU32`
+// so it doesn't have padding and uninitialized bytes.
+unsafe impl NoUninit for TypeId {}
+
+// SAFETY: `TypeId` is `repr(transparent)` wrapper around `NonZeroU32`
+// so `None` is guaranteed to be represented by zero.
+unsafe impl ZeroableInOption for TypeId {}
+
+// SAFETY: `TypeId` has same layout as `u32`,
+// and we accept only values that satisfy invariants of `TypeId`.
+unsafe impl CheckedBitPattern for TypeId {
+    type Bits = u32;
+
+    #[inline]
+    fn is_valid_bit_pattern(bits: &u32) -> bool {
+        TypeId::from_u32(*bits).is_ok()
+    }
+}
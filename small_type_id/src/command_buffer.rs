@@ -0,0 +1,362 @@
+//! Contiguous buffer of values of different types.
+//!
+//! Available only if feature [`alloc`](crate#feature-alloc) is enabled.
+//!
+//! [`CommandBuffer`] stores every value after 4 byte [`TypeId`] header, aligned as necessary,
+//! in single allocation. Information about size, alignment and drop of every type is stored once per type.
+//! Values can be visited as `&dyn SmallAny` or moved out into callbacks registered in [`Handlers`].
+//!
+//! ```
+//! use std::cell::RefCell;
+//!
+//! use small_type_id::command_buffer::{CommandBuffer, Handlers};
+//!
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Move(i32, i32);
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Draw(&'static str);
+//!
+//! let mut commands = CommandBuffer::new();
+//! commands.push(Move(1, 2));
+//! commands.push(Draw("player"));
+//! commands.push(Move(3, 4));
+//! assert_eq!(commands.len(), 3);
+//!
+//! let log = RefCell::new(Vec::new());
+//! let mut handlers = Handlers::new();
+//! handlers
+//!     .on(|Move(x, y)| log.borrow_mut().push(format!("move {x} {y}")))
+//!     .on(|Draw(what)| log.borrow_mut().push(format!("draw {what}")));
+//! commands.drain_with(&mut handlers);
+//! drop(handlers);
+//!
+//! assert!(commands.is_empty());
+//! assert_eq!(log.into_inner(), ["move 1 2", "draw player", "move 3 4"]);
+//! ```
+
+use alloc::alloc::{Layout, alloc, dealloc, handle_alloc_error, realloc};
+use alloc::boxed::Box;
+use core::ptr::NonNull;
+
+use crate::any::SmallAny;
+use crate::hash::TypeIdMap;
+use crate::{HasTypeId, TypeId};
+
+/// Buffer that stores values of any types that implement [`HasTypeId`] in order of insertion.
+///
+/// See [module documentation](self) for example.
+pub struct CommandBuffer {
+    // Allocated with `align`, if `cap` is not zero.
+    ptr: NonNull<u8>,
+    cap: usize,
+    align: usize,
+    // Number of used bytes.
+    end: usize,
+    len: usize,
+    // Contains entry for every type that is pushed into buffer.
+    types: TypeIdMap<VTable>,
+}
+
+/// Callbacks for values of specific types used by [`CommandBuffer::drain_with`].
+pub struct Handlers<'h> {
+    // Handlers receive pointer to value moved out of buffer.
+    handlers: TypeIdMap<Box<dyn FnMut(*mut u8) + 'h>>,
+}
+
+#[derive(Clone, Copy)]
+struct VTable {
+    layout: Layout,
+    drop: unsafe fn(*mut u8),
+    as_dyn: fn(*const u8) -> *const dyn SmallAny,
+}
+
+/// # Safety
+///
+/// `ptr` must point to valid value of type `T` which is not used after call.
+unsafe fn drop_value<T>(ptr: *mut u8) {
+    // SAFETY: Ensured by caller.
+    unsafe { ptr.cast::<T>().drop_in_place() }
+}
+
+fn as_dyn<T: HasTypeId>(ptr: *const u8) -> *const dyn SmallAny {
+    ptr.cast::<T>()
+}
+
+impl VTable {
+    fn of<T: HasTypeId>() -> Self {
+        Self {
+            layout: Layout::new::<T>(),
+            drop: drop_value::<T>,
+            as_dyn: as_dyn::<T>,
+        }
+    }
+}
+
+const HEADER: Layout = Layout::new::<TypeId>();
+
+/// Returns offset of value and offset of next record for record at `offset`.
+fn record_offsets(offset: usize, layout: Layout) -> (usize, usize) {
+    let value = (offset + HEADER.size()).next_multiple_of(layout.align());
+    let next = (value + layout.size()).next_multiple_of(HEADER.align());
+    (value, next)
+}
+
+impl CommandBuffer {
+    /// Creates empty buffer without allocating.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            cap: 0,
+            align: HEADER.align(),
+            end: 0,
+            len: 0,
+            types: TypeIdMap::default(),
+        }
+    }
+
+    /// Creates empty buffer that can hold at least `bytes` bytes of values and headers without reallocation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` overflows `isize`.
+    #[must_use]
+    pub fn with_capacity(bytes: usize) -> Self {
+        let mut res = Self::new();
+        // Zero sized allocations are not allowed.
+        if bytes > 0 {
+            res.grow(bytes, HEADER.align());
+        }
+        res
+    }
+
+    /// Returns number of stored values.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if buffer contains no values.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns number of bytes that buffer can hold without reallocation.
+    #[must_use]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn grow(&mut self, min_cap: usize, align: usize) {
+        let new_cap = if min_cap <= self.cap {
+            self.cap
+        } else {
+            min_cap.max(self.cap * 2).max(64)
+        };
+        let new_align = align.max(self.align);
+        let new_layout = Layout::from_size_align(new_cap, new_align).expect("capacity overflow");
+        // SAFETY: `new_layout` has non zero size. Old allocation, if any, has layout
+        // with `self.cap` and `self.align`, and only first `self.end` bytes are copied.
+        let new_ptr = unsafe {
+            if self.cap == 0 {
+                alloc(new_layout)
+            } else {
+                let old_layout = Layout::from_size_align_unchecked(self.cap, self.align);
+                if new_align == self.align {
+                    realloc(self.ptr.as_ptr(), old_layout, new_cap)
+                } else {
+                    let new_ptr = alloc(new_layout);
+                    if !new_ptr.is_null() {
+                        new_ptr.copy_from_nonoverlapping(self.ptr.as_ptr(), self.end);
+                        dealloc(self.ptr.as_ptr(), old_layout);
+                    }
+                    new_ptr
+                }
+            }
+        };
+        self.ptr = NonNull::new(new_ptr).unwrap_or_else(|| handle_alloc_error(new_layout));
+        self.cap = new_cap;
+        self.align = new_align;
+    }
+
+    /// Appends value to the end of buffer.
+    ///
+    /// Calls [`ensure_verified`](crate::ensure_verified) first
+    /// because stored values are dropped using vtables found by [`TypeId`]s.
+    ///
+    /// # Panics
+    ///
+    /// Panics if required capacity overflows `isize`.
+    pub fn push<T: HasTypeId>(&mut self, val: T) {
+        // Values are dropped and viewed using vtables found by `TypeId`s
+        // so they must be unique before first value is stored.
+        crate::implementation::ensure_verified();
+        let layout = Layout::new::<T>();
+        let (value, next) = record_offsets(self.end, layout);
+        if next > self.cap || layout.align() > self.align {
+            self.grow(next, layout.align());
+        }
+        self.types.entry(T::TYPE_ID).or_insert_with(VTable::of::<T>);
+        // SAFETY: Buffer has enough capacity, `value` is aligned for `T`
+        // because buffer is aligned at least to alignment of `T`,
+        // and `self.end` is aligned for `TypeId`.
+        #[allow(clippy::cast_ptr_alignment)]
+        unsafe {
+            self.ptr
+                .as_ptr()
+                .add(self.end)
+                .cast::<TypeId>()
+                .write(T::TYPE_ID);
+            self.ptr.as_ptr().add(value).cast::<T>().write(val);
+        }
+        self.end = next;
+        self.len += 1;
+    }
+
+    /// Iterates over stored values in order of insertion.
+    pub fn iter(&self) -> impl Iterator<Item = (TypeId, &dyn SmallAny)> {
+        let mut offset = 0;
+        core::iter::from_fn(move || {
+            if offset == self.end {
+                return None;
+            }
+            // SAFETY: There is record at `offset` because it is less than `self.end`.
+            let (type_id, value, next) = unsafe { self.read_record(offset) };
+            offset = next;
+            let vtable = &self.types[&type_id];
+            // SAFETY: Value lives as long as `self` is borrowed.
+            Some((type_id, unsafe { &*(vtable.as_dyn)(value) }))
+        })
+    }
+
+    /// # Safety
+    ///
+    /// There must be a record at `offset`.
+    unsafe fn read_record(&self, offset: usize) -> (TypeId, *mut u8, usize) {
+        // SAFETY: Ensured by caller. Records start at offsets aligned for `TypeId`.
+        #[allow(clippy::cast_ptr_alignment)]
+        let type_id = unsafe { self.ptr.as_ptr().add(offset).cast::<TypeId>().read() };
+        let (value, next) = record_offsets(offset, self.types[&type_id].layout);
+        // SAFETY: Value is located inside of allocation.
+        (type_id, unsafe { self.ptr.as_ptr().add(value) }, next)
+    }
+
+    /// Moves every value out of buffer in order of insertion
+    /// and passes it to handler registered for its type.
+    /// Values without handlers are dropped.
+    ///
+    /// Keeps allocated memory so buffer can be reused for next batch of values.
+    /// If handler panics, remaining values are dropped.
+    pub fn drain_with(&mut self, handlers: &mut Handlers<'_>) {
+        let mut drain = Drain {
+            offset: 0,
+            end: self.end,
+            buffer: self,
+        };
+        // Values are considered moved out even if handler panics.
+        drain.buffer.end = 0;
+        drain.buffer.len = 0;
+        while drain.offset < drain.end {
+            // SAFETY: There is record at `drain.offset` because it is less than `drain.end`.
+            let (type_id, value, next) = unsafe { drain.buffer.read_record(drain.offset) };
+            drain.offset = next;
+            match handlers.handlers.get_mut(&type_id) {
+                Some(handler) => handler(value),
+                // SAFETY: Value is valid and is not used after this.
+                None => unsafe { (drain.buffer.types[&type_id].drop)(value) },
+            }
+        }
+    }
+
+    /// Drops all values.
+    /// Keeps allocated memory.
+    pub fn clear(&mut self) {
+        self.drain_with(&mut Handlers::new());
+    }
+}
+
+/// Drops remaining values if handler panics.
+struct Drain<'a> {
+    buffer: &'a mut CommandBuffer,
+    offset: usize,
+    end: usize,
+}
+
+impl Drop for Drain<'_> {
+    fn drop(&mut self) {
+        while self.offset < self.end {
+            // SAFETY: There is record at `self.offset` because it is less than `self.end`.
+            let (type_id, value, next) = unsafe { self.buffer.read_record(self.offset) };
+            self.offset = next;
+            // SAFETY: Value is valid and is not used after this.
+            unsafe { (self.buffer.types[&type_id].drop)(value) };
+        }
+    }
+}
+
+impl Drop for CommandBuffer {
+    fn drop(&mut self) {
+        self.clear();
+        if self.cap != 0 {
+            // SAFETY: Buffer was allocated with this layout.
+            unsafe {
+                let layout = Layout::from_size_align_unchecked(self.cap, self.align);
+                dealloc(self.ptr.as_ptr(), layout);
+            }
+        }
+    }
+}
+
+impl Default for CommandBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lists [`TypeId`]s of stored values in order of insertion.
+impl core::fmt::Debug for CommandBuffer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entries(self.iter().map(|(type_id, _)| type_id))
+            .finish()
+    }
+}
+
+impl<'h> Handlers<'h> {
+    /// Creates empty set of handlers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            handlers: TypeIdMap::default(),
+        }
+    }
+
+    /// Registers handler for values of type `T`, replacing previous one.
+    pub fn on<T: HasTypeId>(&mut self, mut handler: impl FnMut(T) + 'h) -> &mut Self {
+        // Handlers are found by `TypeId`s of values so they must be unique.
+        crate::implementation::ensure_verified();
+        let handler = move |ptr: *mut u8| {
+            // SAFETY: Handler is registered for `T::TYPE_ID`
+            // so it is called only for values of type `T`.
+            handler(unsafe { ptr.cast::<T>().read() });
+        };
+        self.handlers.insert(T::TYPE_ID, Box::new(handler));
+        self
+    }
+
+    /// Returns `true` if there is handler for type `T`.
+    #[must_use]
+    pub fn handles<T: HasTypeId>(&self) -> bool {
+        self.handlers.contains_key(&T::TYPE_ID)
+    }
+}
+
+impl Default for Handlers<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
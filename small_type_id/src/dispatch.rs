@@ -0,0 +1,252 @@
+//! Tables that map [`TypeId`]s of known types to values.
+//!
+//! [`DispatchTable`] is built in const context using perfect hashing,
+//! so lookups take constant time and don't require any runtime initialization.
+//! Use [`dispatch_table!`](crate::dispatch_table) macro to build it from list of types.
+//!
+//! ```
+//! use small_type_id::any::SmallAny;
+//! use small_type_id::dispatch::DispatchTable;
+//!
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Click(i32, i32);
+//! #[derive(small_type_id::HasTypeId)]
+//! struct KeyPress(char);
+//!
+//! fn on_click(event: &dyn SmallAny) -> String {
+//!     let Click(x, y) = event.downcast_ref().unwrap();
+//!     format!("click at {x},{y}")
+//! }
+//!
+//! fn on_key(event: &dyn SmallAny) -> String {
+//!     let KeyPress(c) = event.downcast_ref().unwrap();
+//!     format!("key {c}")
+//! }
+//!
+//! static ROUTES: DispatchTable<fn(&dyn SmallAny) -> String, 2> = small_type_id::dispatch_table! {
+//!     Click => on_click,
+//!     KeyPress => on_key,
+//! };
+//!
+//! let event: &dyn SmallAny = &KeyPress('q');
+//! let handler = ROUTES.get(event.small_type_id()).unwrap();
+//! assert_eq!(handler(event), "key q");
+//! ```
+//!
+//! Tables with duplicate [`TypeId`]s fail to compile:
+//!
+//! ```compile_fail
+//! # use small_type_id::dispatch::DispatchTable;
+//! #[derive(small_type_id::HasTypeId)]
+//! struct A;
+//!
+//! static TABLE: DispatchTable<u32, 2> = small_type_id::dispatch_table! { A => 1, A => 2 };
+//! ```
+
+use crate::TypeId;
+
+/// Map from [`TypeId`]s to values of type `V` with `N` entries that is built in const context.
+///
+/// Construct it using [`DispatchTable::new`] or [`dispatch_table!`](crate::dispatch_table) macro.
+/// See [module documentation](self) for example.
+pub struct DispatchTable<V, const N: usize> {
+    // Displacement for every bucket.
+    displacements: [u32; N],
+    // Every entry is placed into slot selected by displacement of its bucket.
+    entries: [(TypeId, V); N],
+}
+
+/// Number of displacements tried for every bucket before giving up.
+const MAX_DISPLACEMENT: u32 = 1 << 16;
+
+/// Maps hash uniformly to `0..n`.
+const fn reduce(hash: u32, n: usize) -> usize {
+    ((hash as u64 * n as u64) >> 32) as usize
+}
+
+/// Finalizer of `MurmurHash3`.
+const fn mix(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xC2B2_AE35);
+    h ^= h >> 16;
+    h
+}
+
+const fn bucket_of(type_id: TypeId, n: usize) -> usize {
+    // Type ids are usually hashes already but they can be sequential if set manually.
+    reduce(mix(type_id.as_u32()), n)
+}
+
+const fn slot_of(type_id: TypeId, displacement: u32, n: usize) -> usize {
+    let seed = displacement.wrapping_mul(0x9E37_79B9) ^ 0x5BD1_E995;
+    reduce(mix(type_id.as_u32() ^ seed), n)
+}
+
+impl<V, const N: usize> DispatchTable<V, N> {
+    /// Builds table from pairs of [`TypeId`]s and values.
+    ///
+    /// Intended to be used in const context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `entries` contain duplicate [`TypeId`]s, which is a compilation error in const context.
+    #[must_use]
+    pub const fn new(mut entries: [(TypeId, V); N]) -> Self {
+        // Initial values are overwritten below.
+        let mut ids = [TypeId(core::num::NonZeroU32::MIN); N];
+        let mut i = 0;
+        while i < N {
+            ids[i] = entries[i].0;
+            i += 1;
+        }
+        assert!(
+            crate::konst::is_unique(&ids),
+            "Duplicate TypeId in DispatchTable"
+        );
+
+        // Group entries by buckets using counting sort.
+        let mut bucket_sizes = [0usize; N];
+        let mut i = 0;
+        while i < N {
+            bucket_sizes[bucket_of(ids[i], N)] += 1;
+            i += 1;
+        }
+        let mut bucket_starts = [0usize; N];
+        let mut max_size = 0;
+        let mut b = 1;
+        while b < N {
+            bucket_starts[b] = bucket_starts[b - 1] + bucket_sizes[b - 1];
+            b += 1;
+        }
+        let mut by_bucket = [0usize; N];
+        let mut filled = [0usize; N];
+        let mut i = 0;
+        while i < N {
+            let b = bucket_of(ids[i], N);
+            by_bucket[bucket_starts[b] + filled[b]] = i;
+            filled[b] += 1;
+            if filled[b] > max_size {
+                max_size = filled[b];
+            }
+            i += 1;
+        }
+
+        // Place large buckets first because they are hardest to place.
+        let mut displacements = [0u32; N];
+        let mut occupied = [false; N];
+        // Target slot for every entry.
+        let mut slots = [0usize; N];
+        let mut size = max_size;
+        while size > 0 {
+            let mut b = 0;
+            while b < N {
+                if bucket_sizes[b] == size {
+                    let start = bucket_starts[b];
+                    let members = by_bucket.split_at(start).1.split_at(size).0;
+                    displacements[b] = find_displacement(&ids, members, &mut occupied, &mut slots);
+                }
+                b += 1;
+            }
+            size -= 1;
+        }
+
+        // Move every entry into its slot by following cycles of permutation.
+        let mut i = 0;
+        while i < N {
+            while slots[i] != i {
+                let target = slots[i];
+                entries.swap(i, target);
+                slots.swap(i, target);
+            }
+            i += 1;
+        }
+
+        Self {
+            displacements,
+            entries,
+        }
+    }
+
+    /// Returns number of entries.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if table has no entries.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns value for `type_id`.
+    #[must_use]
+    #[inline]
+    pub const fn get(&self, type_id: TypeId) -> Option<&V> {
+        if N == 0 {
+            return None;
+        }
+        let displacement = self.displacements[bucket_of(type_id, N)];
+        let entry = &self.entries[slot_of(type_id, displacement, N)];
+        if entry.0.eq(&type_id) {
+            Some(&entry.1)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if table has value for `type_id`.
+    #[must_use]
+    #[inline]
+    pub const fn contains(&self, type_id: TypeId) -> bool {
+        self.get(type_id).is_some()
+    }
+
+    /// Iterates over entries in unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = (TypeId, &V)> {
+        self.entries.iter().map(|(k, v)| (*k, v))
+    }
+}
+
+/// Finds displacement that puts every entry of bucket into free slot,
+/// marks those slots as occupied and remembers them in `slots`.
+const fn find_displacement<const N: usize>(
+    ids: &[TypeId; N],
+    members: &[usize],
+    occupied: &mut [bool; N],
+    slots: &mut [usize; N],
+) -> u32 {
+    let mut displacement = 0;
+    'search: while displacement < MAX_DISPLACEMENT {
+        let mut i = 0;
+        while i < members.len() {
+            let entry = members[i];
+            let slot = slot_of(ids[entry], displacement, N);
+            if occupied[slot] {
+                // Undo marks made for this displacement.
+                let mut j = 0;
+                while j < i {
+                    occupied[slots[members[j]]] = false;
+                    j += 1;
+                }
+                displacement += 1;
+                continue 'search;
+            }
+            occupied[slot] = true;
+            slots[entry] = slot;
+            i += 1;
+        }
+        return displacement;
+    }
+    panic!("Failed to build DispatchTable");
+}
+
+impl<V: core::fmt::Debug, const N: usize> core::fmt::Debug for DispatchTable<V, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,275 @@
+//! Allocation free algorithms for finding duplicate ids.
+//!
+//! Uses only `core` because it is also included by benchmarks.
+
+/// Number of ids that are processed at once by [`ChunkedDuplicates`].
+pub(crate) const CHUNK_LEN: usize = 2048;
+
+const DUPLICATE_MARK: u32 = 1 << 31;
+
+/// Iterates over every duplicated id once, in unspecified order.
+///
+/// Used when ids cannot be sorted in place.
+/// Ids are processed by chunks which are sorted in a buffer on stack.
+/// Then every later id is looked up in the chunk using binary search.
+/// Found ids are marked by setting their most significant bit,
+/// which is guaranteed to be zero for valid `TypeId`s.
+///
+/// Runs in _O(n<sup>2</sup>)_ time although with small constant.
+pub(crate) struct ChunkedDuplicates<I> {
+    /// Iterator over all ids, used to skip ids reported in previous chunks.
+    all: I,
+    /// Iterator over ids after current chunk.
+    rest: I,
+    /// Number of ids before current chunk.
+    before_chunk: usize,
+    buffer: [u32; CHUNK_LEN],
+    len: usize,
+    pos: usize,
+}
+
+impl<I> ChunkedDuplicates<I>
+where
+    I: Iterator<Item = u32> + Clone,
+{
+    pub(crate) fn new(ids: I) -> Self {
+        Self {
+            all: ids.clone(),
+            rest: ids,
+            before_chunk: 0,
+            buffer: [0; CHUNK_LEN],
+            len: 0,
+            pos: 0,
+        }
+    }
+
+    /// Returns false if there are no more ids.
+    fn load_next_chunk(&mut self) -> bool {
+        self.before_chunk += self.len;
+        self.pos = 0;
+        self.len = 0;
+        for id in self.rest.by_ref() {
+            self.buffer[self.len] = id;
+            self.len += 1;
+            if self.len == self.buffer.len() {
+                break;
+            }
+        }
+        if self.len == 0 {
+            return false;
+        }
+        let chunk = &mut self.buffer[..self.len];
+        chunk.sort_unstable();
+
+        for i in 1..chunk.len() {
+            if chunk[i - 1] & !DUPLICATE_MARK == chunk[i] {
+                chunk[i - 1] |= DUPLICATE_MARK;
+                chunk[i] |= DUPLICATE_MARK;
+            }
+        }
+        for id in self.rest.clone() {
+            if let Ok(found) = chunk.binary_search_by_key(&id, |x| x & !DUPLICATE_MARK) {
+                chunk[found] |= DUPLICATE_MARK;
+            }
+        }
+        true
+    }
+}
+
+impl<I> Iterator for ChunkedDuplicates<I>
+where
+    I: Iterator<Item = u32> + Clone,
+{
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            if self.pos == self.len && !self.load_next_chunk() {
+                return None;
+            }
+            let chunk = &self.buffer[..self.len];
+            let id = chunk[self.pos] & !DUPLICATE_MARK;
+            // Skip whole run of same values because
+            // binary search marks only one of them.
+            let mut is_duplicate = false;
+            while self.pos < chunk.len() && chunk[self.pos] & !DUPLICATE_MARK == id {
+                is_duplicate |= chunk[self.pos] & DUPLICATE_MARK != 0;
+                self.pos += 1;
+            }
+            if !is_duplicate {
+                continue;
+            }
+            let reported_earlier = self.all.clone().take(self.before_chunk).any(|x| x == id);
+            if !reported_earlier {
+                return Some(id);
+            }
+        }
+    }
+}
+
+/// Iterates over every id that is duplicated in a set of sorted slices once, in ascending order.
+///
+/// Every next value is looked up in every slice using binary search
+/// so it runs in _O(n * k * log(n))_ time where _k_ is number of slices.
+pub(crate) struct SortedDuplicates<S> {
+    slices: S,
+    prev: Option<u32>,
+}
+
+impl<'a, S> SortedDuplicates<S>
+where
+    S: Iterator<Item = &'a [u32]> + Clone,
+{
+    pub(crate) fn new(slices: S) -> Self {
+        Self { slices, prev: None }
+    }
+}
+
+impl<'a, S> Iterator for SortedDuplicates<S>
+where
+    S: Iterator<Item = &'a [u32]> + Clone,
+{
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            let prev = self.prev;
+            let after_prev = move |s: &[u32]| match prev {
+                Some(p) => s.partition_point(|&x| x <= p),
+                None => 0,
+            };
+            let val = self
+                .slices
+                .clone()
+                .filter_map(|s| s.get(after_prev(s)).copied())
+                .min()?;
+            let count: usize = self
+                .slices
+                .clone()
+                .map(|s| s.partition_point(|&x| x <= val) - after_prev(s))
+                .sum();
+            self.prev = Some(val);
+            if count > 1 {
+                return Some(val);
+            }
+        }
+    }
+}
+
+/// Checks if there are any duplicated ids in a set of sorted slices.
+///
+/// Faster than [`SortedDuplicates`] if there is only one slice.
+pub(crate) fn has_sorted_duplicates<'a, S>(slices: S) -> bool
+where
+    S: Iterator<Item = &'a [u32]> + Clone,
+{
+    let mut it = slices.clone();
+    match (it.next(), it.next()) {
+        (None, _) => false,
+        (Some(single), None) => single.windows(2).any(|w| w[0] == w[1]),
+        (Some(_), Some(_)) => SortedDuplicates::new(slices).next().is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::{Rng as _, SeedableRng as _};
+
+    use super::*;
+
+    fn chunked(ids: &[u32]) -> Vec<u32> {
+        ChunkedDuplicates::new(ids.iter().copied()).collect()
+    }
+
+    fn sorted(slices: &[&[u32]]) -> Vec<u32> {
+        let sorted: Vec<Vec<u32>> = slices
+            .iter()
+            .map(|s| {
+                let mut s = s.to_vec();
+                s.sort_unstable();
+                s
+            })
+            .collect();
+        let res: Vec<u32> = SortedDuplicates::new(sorted.iter().map(Vec::as_slice)).collect();
+        assert_eq!(
+            has_sorted_duplicates(sorted.iter().map(Vec::as_slice)),
+            !res.is_empty()
+        );
+        res
+    }
+
+    fn naive(ids: &[u32]) -> Vec<u32> {
+        let mut expected: Vec<u32> = Vec::new();
+        for (i, id) in ids.iter().enumerate() {
+            if !ids[..i].contains(id) && ids[i + 1..].contains(id) {
+                expected.push(*id);
+            }
+        }
+        expected.sort_unstable();
+        expected
+    }
+
+    #[test]
+    fn duplicates_in_single_chunk() {
+        assert_eq!(chunked(&[]), []);
+        assert_eq!(chunked(&[1, 2, 3]), []);
+        assert_eq!(chunked(&[3, 1, 3]), [3]);
+        assert_eq!(chunked(&[5, 3, 5, 1, 3, 5]), [3, 5]);
+        assert_eq!(chunked(&[0x7FFF_FFFF, 1, 0x7FFF_FFFF]), [0x7FFF_FFFF]);
+    }
+
+    #[test]
+    fn duplicates_in_multiple_chunks() {
+        let mut ids: Vec<u32> = (1..=3 * CHUNK_LEN as u32 + 5).collect();
+        // Duplicate of value from first chunk in second chunk.
+        ids[CHUNK_LEN + 1] = 7;
+        // And in third chunk.
+        ids[2 * CHUNK_LEN + 3] = 7;
+        // Duplicates inside second chunk.
+        ids[CHUNK_LEN + 10] = ids[CHUNK_LEN + 20];
+        // Duplicate from second chunk in last.
+        let last = ids.len() - 1;
+        ids[last] = ids[CHUNK_LEN + 100];
+        let expected = [7, ids[CHUNK_LEN + 20], ids[CHUNK_LEN + 100]];
+        assert_eq!(chunked(&ids), expected);
+        assert_eq!(sorted(&[&ids]), expected);
+    }
+
+    #[test]
+    fn duplicates_in_sorted_slices() {
+        assert_eq!(sorted(&[]), []);
+        assert_eq!(sorted(&[&[]]), []);
+        assert_eq!(sorted(&[&[], &[]]), []);
+        assert_eq!(sorted(&[&[1, 2, 3]]), []);
+        assert_eq!(sorted(&[&[1, 2, 3], &[4, 5]]), []);
+        assert_eq!(sorted(&[&[1, 2, 2, 3]]), [2]);
+        assert_eq!(sorted(&[&[1, 2, 3], &[3, 4]]), [3]);
+        assert_eq!(sorted(&[&[5, 1, 3], &[3, 4], &[5, 5, 0x7FFF_FFFF]]), [3, 5]);
+        assert_eq!(
+            sorted(&[&[0x7FFF_FFFF], &[1], &[0x7FFF_FFFF]]),
+            [0x7FFF_FFFF]
+        );
+    }
+
+    #[test]
+    fn duplicates_match_naive_implementation() {
+        let mut rng = SmallRng::seed_from_u64(5464);
+        let len = if cfg!(miri) { 300 } else { 5000 };
+        let ids: Vec<u32> = (0..len)
+            .map(|_| rng.random_range(1..(len as u32) * 4))
+            .collect();
+        let expected = naive(&ids);
+        assert!(!expected.is_empty());
+
+        let mut found = chunked(&ids);
+        assert_eq!(found.len(), expected.len());
+        found.sort_unstable();
+        assert_eq!(found, expected);
+
+        assert_eq!(sorted(&[&ids]), expected);
+        let (a, b) = ids.split_at(len / 3);
+        let (b, c) = b.split_at(len / 3);
+        assert_eq!(sorted(&[a, b, c]), expected);
+    }
+}
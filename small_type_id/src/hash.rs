@@ -0,0 +1,124 @@
+//! Hasher for [`TypeId`] keys.
+//!
+//! [`TypeId`] is already a hash of type name so hashing it again using
+//! [`SipHash`](https://doc.rust-lang.org/std/collections/hash_map/struct.RandomState.html)
+//! is a waste of time. [`BuildTypeIdHasher`] produces hashers which use value of [`TypeId`] directly.
+//!
+//! ```
+//! use std::collections::HashMap;
+//!
+//! use small_type_id::TypeId;
+//! use small_type_id::hash::BuildTypeIdHasher;
+//!
+//! #[derive(small_type_id::HasTypeId)]
+//! struct A;
+//!
+//! let mut names: HashMap<TypeId, &str, BuildTypeIdHasher> = HashMap::default();
+//! names.insert(TypeId::of::<A>(), "A");
+//! assert_eq!(names[&TypeId::of::<A>()], "A");
+//! ```
+//!
+//! If feature [`alloc`](crate#feature-alloc) is enabled, this module also provides
+//! [`TypeIdMap`] and [`TypeIdSet`] aliases for collections of [`hashbrown`][1] crate.
+//!
+//! [1]: https://crates.io/crates/hashbrown
+
+use core::hash::{BuildHasher, Hasher};
+
+#[cfg(doc)]
+use crate::TypeId;
+
+/// Hash map with [`TypeId`] keys that uses [`BuildTypeIdHasher`].
+///
+/// Available only if feature [`alloc`](crate#feature-alloc) is enabled.
+#[cfg(feature = "alloc")]
+pub type TypeIdMap<V> = hashbrown::HashMap<crate::TypeId, V, BuildTypeIdHasher>;
+
+/// Hash set of [`TypeId`]s that uses [`BuildTypeIdHasher`].
+///
+/// Available only if feature [`alloc`](crate#feature-alloc) is enabled.
+#[cfg(feature = "alloc")]
+pub type TypeIdSet = hashbrown::HashSet<crate::TypeId, BuildTypeIdHasher>;
+
+/// Creates [`TypeIdHasher`]s.
+///
+/// Can be used with any hash map, e.g. `std::collections::HashMap<TypeId, V, BuildTypeIdHasher>`.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct BuildTypeIdHasher;
+
+/// Hasher that uses value of [`TypeId`] as hash.
+///
+/// Since hash tables like [`hashbrown`][1] use most significant bits of hash as tags
+/// and least significant bits as position in table, value of [`TypeId`] is copied to both.
+///
+/// It is intended only for hashing [`TypeId`]s.
+/// Other values are hashed too, but with poor quality.
+///
+/// [1]: https://crates.io/crates/hashbrown
+#[derive(Clone, Copy, Default, Debug)]
+pub struct TypeIdHasher {
+    state: u32,
+}
+
+impl BuildHasher for BuildTypeIdHasher {
+    type Hasher = TypeIdHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> TypeIdHasher {
+        TypeIdHasher::default()
+    }
+}
+
+impl Hasher for TypeIdHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        let val = u64::from(self.state);
+        val | (val << 33)
+    }
+
+    /// Called by [`Hash`](core::hash::Hash) implementation of [`TypeId`].
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        // Keeps value unchanged if it is the only one written.
+        self.state = self.state.rotate_left(5) ^ i;
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(4);
+        for chunk in &mut chunks {
+            self.write_u32(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut buf = [0; 4];
+            buf[..rest.len()].copy_from_slice(rest);
+            self.write_u32(u32::from_le_bytes(buf));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TypeId;
+
+    #[test]
+    fn uses_value_of_type_id() {
+        for val in [1, 0xABC, 0x1234_5678, 0x7FFF_FFFF] {
+            let type_id = TypeId::from_u32(val).unwrap();
+            let hash = BuildTypeIdHasher.hash_one(type_id);
+            assert_eq!(hash as u32, val);
+            assert_eq!((hash >> 33) as u32, val);
+            assert_eq!(hash >> 57, u64::from(val >> 24));
+        }
+    }
+
+    #[test]
+    fn hashes_other_values() {
+        let h = |x: &[u8]| BuildTypeIdHasher.hash_one(x);
+        assert_ne!(h(b"abc"), h(b"abd"));
+        assert_ne!(h(b"abcdefgh"), h(b"abcdefgi"));
+        assert_ne!(h(b""), h(b"a"));
+        assert_eq!(h(b"abcde"), h(b"abcde"));
+    }
+}
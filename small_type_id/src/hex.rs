@@ -12,7 +12,7 @@ pub(crate) struct HexView {
 impl HexView {
     #[inline]
     pub(crate) fn as_str(&self) -> &str {
-        // SAFETY: Only values in range b'0'..=b'9' and b'A'..=b'F'.
+        // SAFETY: Only values in range b'0'..=b'9', b'A'..=b'F' and b'a'..=b'f'.
         // It makes it valid ASCII string.
         // len cannot be bigger than MAX_HEX_DIGITS by construction.
         unsafe {
@@ -21,8 +21,20 @@ impl HexView {
         }
     }
 
-    #[allow(clippy::items_after_statements)]
+    /// Uses uppercase letters.
+    #[inline]
     pub(crate) const fn new(val: u32) -> HexView {
+        Self::with_letters(val, b'A')
+    }
+
+    /// Uses lowercase letters.
+    #[inline]
+    pub(crate) const fn new_lower(val: u32) -> HexView {
+        Self::with_letters(val, b'a')
+    }
+
+    #[allow(clippy::items_after_statements)]
+    const fn with_letters(val: u32, first_letter: u8) -> HexView {
         let len: u32 = if val == 0 {
             1
         } else {
@@ -45,8 +57,8 @@ impl HexView {
         let decimals = x & !mask;
         let letters = x & mask;
         const ADD_DIGITS: u64 = u64::from_le_bytes([b'0'; 8]);
-        const ADD_LETTERS: u64 = u64::from_le_bytes([b'A' - 10; 8]);
-        let x = ((decimals + ADD_DIGITS) & !mask) | ((letters + ADD_LETTERS) & mask);
+        let add_letters: u64 = u64::from_le_bytes([first_letter - 10; 8]);
+        let x = ((decimals + ADD_DIGITS) & !mask) | ((letters + add_letters) & mask);
 
         let buffer = x.to_be_bytes();
 
@@ -57,6 +69,34 @@ impl HexView {
     }
 }
 
+/// Parses hexadecimal number in any case without prefix.
+/// Leading zeros are ignored.
+pub(crate) const fn parse(s: &[u8]) -> Option<u32> {
+    if s.is_empty() {
+        return None;
+    }
+    let mut start = 0;
+    while start + 1 < s.len() && s[start] == b'0' {
+        start += 1;
+    }
+    if s.len() - start > MAX_HEX_DIGITS {
+        return None;
+    }
+    let mut res = 0u32;
+    let mut i = start;
+    while i < s.len() {
+        let digit = match s[i] {
+            c @ b'0'..=b'9' => c - b'0',
+            c @ b'A'..=b'F' => c - b'A' + 10,
+            c @ b'a'..=b'f' => c - b'a' + 10,
+            _ => return None,
+        };
+        res = (res << 4) | digit as u32;
+        i += 1;
+    }
+    Some(res)
+}
+
 #[cfg(test)]
 #[allow(clippy::wildcard_imports)]
 mod tests {
@@ -84,14 +124,41 @@ mod tests {
     fn first_100_nums() {
         for i in 0..100 {
             assert_eq!(HexView::new(i).as_str(), format!("{:X}", i));
+            assert_eq!(HexView::new_lower(i).as_str(), format!("{:x}", i));
+            assert_eq!(parse(HexView::new(i).as_str().as_bytes()), Some(i));
         }
     }
 
+    #[test]
+    fn lower_nums() {
+        assert_eq!(HexView::new_lower(0).as_str(), "0");
+        assert_eq!(HexView::new_lower(u32::MAX).as_str(), "ffffffff");
+        assert_eq!(HexView::new_lower(0x90ABCDEF).as_str(), "90abcdef");
+        assert_eq!(HexView::new_lower(0x12345678).as_str(), "12345678");
+    }
+
+    #[test]
+    fn parses() {
+        assert_eq!(parse(b"0"), Some(0));
+        assert_eq!(parse(b"FFFFFFFF"), Some(u32::MAX));
+        assert_eq!(parse(b"90abcDEF"), Some(0x90ABCDEF));
+        assert_eq!(parse(b"00000000ABC"), Some(0xABC));
+        assert_eq!(parse(b"0000"), Some(0));
+        assert_eq!(parse(b""), None);
+        assert_eq!(parse(b"123456789"), None);
+        assert_eq!(parse(b"0x12"), None);
+        assert_eq!(parse(b"+12"), None);
+        assert_eq!(parse(b"12G"), None);
+        assert_eq!(parse(b" 12"), None);
+    }
+
     #[test]
     fn mult_ten() {
         let mut val = 1u32;
         while let Some(x) = val.checked_mul(10) {
             assert_eq!(HexView::new(x).as_str(), format!("{:X}", x));
+            assert_eq!(HexView::new_lower(x).as_str(), format!("{:x}", x));
+            assert_eq!(parse(format!("{:x}", x).as_bytes()), Some(x));
             val = x;
         }
     }
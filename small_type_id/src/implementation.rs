@@ -1,14 +1,16 @@
 use core::num::NonZeroU32;
-#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+#[cfg(not(small_type_id_link_section))]
 use core::ptr;
-#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+#[cfg(not(small_type_id_link_section))]
 use core::sync::atomic::AtomicPtr;
 
 use xxhash_rust::const_xxh32::xxh32;
 
 use crate::TypeId;
+#[cfg(not(feature = "unsafe_dont_register_types"))]
+use crate::duplicates;
 #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
-use crate::hex;
+use crate::{CollisionPolicy, CollisionReport};
 
 // Functions and types used in macro generated code.
 #[doc(hidden)]
@@ -16,6 +18,7 @@ pub mod private {
     #[allow(clippy::wildcard_imports)]
     use super::*;
 
+    #[cfg(not(target_os = "none"))]
     pub use ctor::declarative::ctor;
 
     #[repr(C)]
@@ -23,35 +26,274 @@ pub mod private {
         pub(super) type_id: TypeId,
         #[cfg(feature = "debug_type_name")]
         pub(super) type_name: &'static str,
-        #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+        #[cfg(feature = "debug_type_name")]
+        pub(super) crate_version: &'static str,
+        #[cfg(feature = "debug_type_name")]
+        pub(super) seed: u32,
+        #[cfg(not(small_type_id_link_section))]
         pub(super) next: AtomicPtr<TypeEntry>,
     }
 
     impl TypeEntry {
         #[must_use]
-        pub const fn new(type_name: &'static str, type_id: TypeId) -> TypeEntry {
-            let _ = type_name;
+        pub const fn new(debug_info: DebugInfo, type_id: TypeId) -> TypeEntry {
+            let _ = debug_info;
 
             Self {
                 type_id,
-                #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+                #[cfg(not(small_type_id_link_section))]
                 next: AtomicPtr::new(ptr::null_mut()),
                 #[cfg(feature = "debug_type_name")]
-                type_name,
+                type_name: debug_info.type_name,
+                #[cfg(feature = "debug_type_name")]
+                crate_version: debug_info.crate_version,
+                #[cfg(feature = "debug_type_name")]
+                seed: debug_info.seed,
             }
         }
     }
 
     #[cold]
-    #[cfg(not(target_os = "windows"))]
-    #[cfg(not(target_os = "linux"))]
-    #[cfg(not(feature = "unsafe_dont_register_types"))]
+    #[cfg(not(small_type_id_link_section))]
+    #[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+    #[cfg(feature = "unsafe_remove_duplicate_checks")]
     pub unsafe fn register_type(entry: &'static TypeEntry) {
         unsafe {
             with_ctors_per_entry::register_type(entry);
         }
     }
 
+    #[cold]
+    #[cfg(not(small_type_id_link_section))]
+    #[cfg(not(any(
+        feature = "unsafe_remove_duplicate_checks",
+        feature = "manual_registry"
+    )))]
+    pub unsafe fn register_type<const HEIGHT: usize>(
+        entry: &'static TypeEntry,
+        node: &'static TypeNode<HEIGHT>,
+    ) {
+        unsafe {
+            with_ctors_per_entry::register_type(entry);
+            with_ctors_per_entry::check_type(node);
+        }
+    }
+
+    /// Node of skip list used to find duplicates.
+    ///
+    /// It is kept separately from [`TypeEntry`]
+    /// because its size depends on height chosen by [`node_height`].
+    #[cfg(not(small_type_id_link_section))]
+    #[cfg(not(any(
+        feature = "unsafe_remove_duplicate_checks",
+        feature = "manual_registry"
+    )))]
+    pub struct TypeNode<const HEIGHT: usize>(
+        pub(super) core::cell::UnsafeCell<crate::skip_list::SkipListNode<TypeId, HEIGHT>>,
+    );
+
+    // SAFETY: Node is modified only once, during registration, while skip list is locked.
+    #[cfg(not(small_type_id_link_section))]
+    #[cfg(not(any(
+        feature = "unsafe_remove_duplicate_checks",
+        feature = "manual_registry"
+    )))]
+    unsafe impl<const HEIGHT: usize> Sync for TypeNode<HEIGHT> {}
+
+    #[cfg(not(small_type_id_link_section))]
+    #[cfg(not(any(
+        feature = "unsafe_remove_duplicate_checks",
+        feature = "manual_registry"
+    )))]
+    impl<const HEIGHT: usize> TypeNode<HEIGHT> {
+        #[must_use]
+        pub const fn new(type_id: TypeId) -> Self {
+            Self(core::cell::UnsafeCell::new(
+                crate::skip_list::SkipListNode::new(type_id),
+            ))
+        }
+    }
+
+    /// Selects height of skip list tower for type.
+    ///
+    /// Type ids are already random so their trailing ones
+    /// give heights with every next level half as likely as previous.
+    /// Most types get only 1 or 2 levels instead of reserving full tower.
+    #[cfg(not(small_type_id_link_section))]
+    #[cfg(not(any(
+        feature = "unsafe_remove_duplicate_checks",
+        feature = "manual_registry"
+    )))]
+    #[must_use]
+    pub const fn node_height(type_id: TypeId) -> usize {
+        let height = type_id.as_u32().trailing_ones() as usize + 1;
+        if height < with_ctors_per_entry::SKIP_LIST_HEIGHT {
+            height
+        } else {
+            with_ctors_per_entry::SKIP_LIST_HEIGHT
+        }
+    }
+
+    /// Copy of [`TypeId`] of registered type which is put by derive macro into writable link section.
+    /// All copies are sorted in place before verification.
+    #[repr(transparent)]
+    pub struct MirroredId(core::cell::UnsafeCell<u32>);
+
+    // SAFETY: Value is modified only during sorting which is synchronized.
+    unsafe impl Sync for MirroredId {}
+
+    impl MirroredId {
+        #[must_use]
+        pub const fn new(type_id: TypeId) -> Self {
+            Self(core::cell::UnsafeCell::new(type_id.as_u32()))
+        }
+
+        #[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+        #[cfg(small_type_id_link_section)]
+        pub(super) const fn zeroed() -> Self {
+            Self(core::cell::UnsafeCell::new(0))
+        }
+    }
+
+    /// Table of types defined by [`registry!`](crate::registry) macro.
+    #[cfg_attr(
+        any(
+            not(feature = "manual_registry"),
+            feature = "unsafe_remove_duplicate_checks"
+        ),
+        allow(dead_code)
+    )]
+    pub struct ManualRegistry {
+        pub(super) entries: &'static [crate::TypeEntry],
+        pub(super) policy: Option<crate::CollisionPolicy>,
+    }
+
+    impl ManualRegistry {
+        #[must_use]
+        pub const fn new(
+            entries: &'static [crate::TypeEntry],
+            policy: Option<crate::CollisionPolicy>,
+        ) -> Self {
+            Self { entries, policy }
+        }
+    }
+
+    #[must_use]
+    pub const fn manual_entry(debug_info: DebugInfo, type_id: TypeId) -> crate::TypeEntry {
+        let _ = debug_info;
+        crate::TypeEntry {
+            type_id,
+            #[cfg(feature = "debug_type_name")]
+            debug_type_name: debug_info.type_name,
+            #[cfg(feature = "debug_type_name")]
+            debug_crate_version: debug_info.crate_version,
+            #[cfg(feature = "debug_type_name")]
+            debug_seed: debug_info.seed,
+        }
+    }
+
+    /// Information about type saved if feature `debug_type_name` is enabled.
+    #[derive(Clone, Copy)]
+    #[cfg_attr(not(feature = "debug_type_name"), allow(dead_code))]
+    pub struct DebugInfo {
+        pub(super) type_name: &'static str,
+        pub(super) crate_version: &'static str,
+        pub(super) seed: u32,
+    }
+
+    impl DebugInfo {
+        pub const UNKNOWN: Self = Self {
+            type_name: UNKNOWN_TYPE_NAME,
+            crate_version: "",
+            seed: 0,
+        };
+
+        #[must_use]
+        pub const fn new(
+            type_name: &'static str,
+            crate_version: Option<&'static str>,
+            seed: u32,
+        ) -> Self {
+            Self {
+                type_name,
+                crate_version: match crate_version {
+                    Some(v) => v,
+                    None => "",
+                },
+                seed,
+            }
+        }
+    }
+
+    /// Put by [`collision_policy!`](crate::collision_policy) macro.
+    ///
+    /// With link sections, pointer to record is put into section shared by all versions
+    /// of `small_type_id` linked into binary so policy selected using one version
+    /// is used by verification of every version.
+    /// Like registry header, layout of existing fields must be kept,
+    /// new fields may be only appended and `POLICY_RECORD_VERSION` must be increased in such case.
+    #[repr(C)]
+    pub struct CollisionPolicyRecord {
+        /// Always [`COLLISION_POLICY_MAGIC`].
+        pub(super) magic: u32,
+        // Fields are checked using `record_size`, version is kept for diagnostics.
+        #[allow(dead_code)]
+        pub(super) record_version: u32,
+        /// Size of this struct in version that put record.
+        pub(super) record_size: usize,
+        pub(super) small_type_id_version_ptr: *const u8,
+        pub(super) small_type_id_version_len: usize,
+        /// Policy encoded by `POLICY_KIND_*` constants which never change.
+        pub(super) kind: u32,
+        /// Read only by the same version because layout of policy,
+        /// and especially signature of hook, may differ between versions.
+        pub(super) policy: crate::CollisionPolicy,
+    }
+
+    // SAFETY: Pointer in record points to immutable static.
+    unsafe impl Sync for CollisionPolicyRecord {}
+
+    impl CollisionPolicyRecord {
+        #[must_use]
+        pub const fn new(policy: crate::CollisionPolicy) -> Self {
+            Self {
+                magic: COLLISION_POLICY_MAGIC,
+                record_version: POLICY_RECORD_VERSION,
+                record_size: size_of::<Self>(),
+                small_type_id_version_ptr: env!("CARGO_PKG_VERSION").as_ptr(),
+                small_type_id_version_len: env!("CARGO_PKG_VERSION").len(),
+                kind: match policy {
+                    crate::CollisionPolicy::Abort => POLICY_KIND_ABORT,
+                    crate::CollisionPolicy::Panic => POLICY_KIND_PANIC,
+                    crate::CollisionPolicy::Warn => POLICY_KIND_WARN,
+                    crate::CollisionPolicy::Hook(_) => POLICY_KIND_HOOK,
+                },
+                policy,
+            }
+        }
+    }
+
+    /// Called by [`collision_policy!`](crate::collision_policy) for policies that continue execution
+    /// so invoker has to write `unsafe` block.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that nothing in binary relies on uniqueness of [`TypeId`]s.
+    #[must_use]
+    pub const unsafe fn continue_on_collisions(
+        policy: crate::CollisionPolicy,
+    ) -> crate::CollisionPolicy {
+        policy
+    }
+
+    #[cfg(not(small_type_id_link_section))]
+    pub unsafe fn set_collision_policy(record: &'static CollisionPolicyRecord) {
+        #[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+        with_ctors_per_entry::set_collision_policy(record);
+        #[cfg(any(feature = "unsafe_dont_register_types", feature = "manual_registry"))]
+        let _ = record;
+    }
+
     /// Use [`compute_input_len`] to compute `TOTAL_INPUT_LEN`.
     #[must_use]
     pub const fn compute_id<const TOTAL_INPUT_LEN: usize>(
@@ -89,186 +331,1090 @@ pub mod private {
             }
     }
 
+    /// Allows to use values of [`TypeId`]s as patterns in `match_type!`
+    /// because `TypeId` itself cannot be matched structurally.
+    pub trait RawTypeId {
+        const RAW: u32;
+    }
+
+    impl<T: crate::HasTypeId + ?Sized> RawTypeId for T {
+        const RAW: u32 = T::TYPE_ID.as_u32();
+    }
+
+    pub use crate::private_macro_collision_policy_section_name as collision_policy_section_name;
+    pub use crate::private_macro_collision_policy_symbol_name as collision_policy_symbol_name;
+    pub use crate::private_macro_count_types as count_types;
+    pub use crate::private_macro_define_registry as define_registry;
+    pub use crate::private_macro_ids_link_section_name as ids_link_section_name;
     pub use crate::private_macro_implement_type_and_register as implement_type_and_register;
     pub use crate::private_macro_implement_type_id as implement_type_id;
     pub use crate::private_macro_link_section_name as link_section_name;
+    pub use crate::private_macro_match_type_arms as match_type_arms;
+    pub use crate::private_macro_policy_value as policy_value;
     pub use crate::private_macro_register_type_id as register_type_id;
+    pub use crate::private_macro_set_collision_policy as set_collision_policy;
     pub use crate::private_macro_small_type_id_version as small_type_id_version;
+    pub use crate::private_macro_unique_type_ids_test as unique_type_ids_test;
 }
 
-pub(crate) fn pub_iter_registered_types() -> impl Iterator<Item = crate::TypeEntry> {
+const COLLISION_POLICY_MAGIC: u32 = u32::from_le_bytes(*b"STCP");
+const POLICY_RECORD_VERSION: u32 = 1;
+const POLICY_KIND_ABORT: u32 = 1;
+const POLICY_KIND_PANIC: u32 = 2;
+const POLICY_KIND_WARN: u32 = 3;
+const POLICY_KIND_HOOK: u32 = 4;
+
+const UNKNOWN_TYPE_NAME: &str = "<unknown type name>";
+
+pub(crate) fn pub_iter_registered_types() -> impl Iterator<Item = crate::TypeEntry> + Clone {
     #[cfg(feature = "unsafe_dont_register_types")]
     {
         core::iter::empty()
     }
     #[cfg(not(feature = "unsafe_dont_register_types"))]
+    #[cfg(feature = "manual_registry")]
     {
-        #[cfg(not(any(target_os = "windows", target_os = "linux")))]
-        let refs = with_ctors_per_entry::iter_registered_types();
-        #[cfg(any(target_os = "windows", target_os = "linux"))]
-        let refs = with_link_section::iter_registered_types();
+        manual_registry::iter_registered_types()
+    }
+    #[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+    {
+        #[cfg(not(small_type_id_link_section))]
+        let entries =
+            with_ctors_per_entry::iter_registered_types().map(private::TypeEntry::to_public);
+        #[cfg(small_type_id_link_section)]
+        let entries = with_link_section::iter_registered_types();
 
-        refs.map(|e| crate::TypeEntry {
-            type_id: e.type_id,
+        entries
+    }
+}
+
+impl private::TypeEntry {
+    #[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+    #[cfg(not(small_type_id_link_section))]
+    fn to_public(&self) -> crate::TypeEntry {
+        crate::TypeEntry {
+            type_id: self.type_id,
             #[cfg(feature = "debug_type_name")]
-            debug_type_name: e.type_name,
-        })
+            debug_type_name: self.type_name,
+            #[cfg(feature = "debug_type_name")]
+            debug_crate_version: self.crate_version,
+            #[cfg(feature = "debug_type_name")]
+            debug_seed: self.seed,
+        }
     }
 }
 
-#[cfg(not(feature = "unsafe_dont_register_types"))]
-#[cfg(any(target_os = "windows", target_os = "linux"))]
+#[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+#[cfg(small_type_id_link_section)]
 mod with_link_section {
     use core::hint::black_box;
-    use core::mem::MaybeUninit;
+    use core::mem::{MaybeUninit, offset_of};
+    use core::sync::atomic::AtomicU8;
+    use core::sync::atomic::Ordering::{Acquire, Release};
 
     #[allow(clippy::wildcard_imports)]
     use super::*;
 
-    pub(super) fn iter_registered_types()
-    -> impl Iterator<Item = &'static private::TypeEntry> + Clone {
-        // Use MaybeUninit because elements in a section may be zeroed padding.
-        #[cfg(target_os = "windows")]
-        let (start_ptr, end_ptr): (
-            *const MaybeUninit<private::TypeEntry>,
-            *const MaybeUninit<private::TypeEntry>,
-        ) = {
-            // We use 1 item for the first element to avoid linking errors
-            // if there is no entries available.
-            #[unsafe(link_section=concat!("smltidrs_small_type_id_rs$", private::small_type_id_version!(), "_a"))]
-            #[used]
-            static START: [MaybeUninit<private::TypeEntry>; 1] = [MaybeUninit::zeroed()];
-            #[unsafe(link_section=concat!("smltidrs_small_type_id_rs$", private::small_type_id_version!(), "_c"))]
-            #[used]
-            static STOP: [MaybeUninit<private::TypeEntry>; 0] = [];
-
-            // Use black_box to prevent provenance based code eliminations.
-            let start_ptr = unsafe { black_box(START.as_ptr().add(1)) };
-            let end_ptr = black_box(STOP.as_ptr());
-
-            (start_ptr, end_ptr)
-        };
-        #[cfg(target_os = "linux")]
-        let (start_ptr, end_ptr): (
-            *const MaybeUninit<private::TypeEntry>,
-            *const MaybeUninit<private::TypeEntry>,
-        ) = {
-            #[unsafe(link_section = private::link_section_name!())]
-            #[used]
-            static AVOID_REMOVAL: MaybeUninit<private::TypeEntry> = MaybeUninit::zeroed();
-            unsafe extern "Rust" {
-                #[link_name = concat!("__start_smltidrs_small_type_id_rs", private::small_type_id_version!())]
-                //#[link_name = concat!("__start_smltidrs_small_type_id_rs")]
-                static START: MaybeUninit<private::TypeEntry>;
-                #[link_name = concat!("__stop_smltidrs_small_type_id_rs", private::small_type_id_version!())]
-                //#[link_name = concat!("__stop_smltidrs_small_type_id_rs")]
-                static STOP: MaybeUninit<private::TypeEntry>;
+    /// Describes entries registered by a single version of `small_type_id` linked into binary.
+    ///
+    /// Every version puts its entries into its own link section
+    /// but also puts a pointer to its header into a link section which name doesn't depend on version.
+    /// Section contains only pointers so it can be walked regardless of size of headers.
+    /// This allows every version to find entries of all other versions
+    /// and verify them together.
+    ///
+    /// Since this struct is read by other versions, its layout must be kept
+    /// and it must contain only fields with stable layout.
+    /// New fields may be only appended to the end and [`HEADER_VERSION`] must be increased in such case.
+    /// Headers put by older versions are smaller so appended fields are read
+    /// only if `header_size` is large enough.
+    #[repr(C)]
+    pub(super) struct RegistryHeader {
+        /// Always [`HEADER_MAGIC`].
+        magic: u32,
+        // Fields are checked using `header_size`, version is kept for diagnostics.
+        #[allow(dead_code)]
+        header_version: u32,
+        /// Size of this struct in version that put header.
+        header_size: usize,
+        // Written for diagnostics made by other versions.
+        #[allow(dead_code)]
+        small_type_id_version_ptr: *const u8,
+        #[allow(dead_code)]
+        small_type_id_version_len: usize,
+        entries_start: *const u8,
+        entries_end: *const u8,
+        entry_size: usize,
+        entry_align: usize,
+        type_id_offset: usize,
+        /// Offset of field with `&'static str` type name
+        /// or `usize::MAX` if names are not saved.
+        type_name_offset: usize,
+        // Fields below are added in version 2.
+        /// Writes number of sorted ids of all entries into argument and returns pointer to them.
+        /// Sorting happens only once, on first call.
+        /// Returns null if ids are not mirrored,
+        /// in such case entries are checked directly.
+        sorted_ids: extern "C" fn(&mut usize) -> *const u32,
+        // Fields below are added in version 3.
+        /// Offset of field with `&'static str` version of crate that declares type
+        /// or `usize::MAX` if it is not saved.
+        crate_version_offset: usize,
+        /// Offset of field with `u32` seed or `usize::MAX` if it is not saved.
+        seed_offset: usize,
+    }
+
+    // SAFETY: Pointers in header point to immutable statics.
+    unsafe impl Sync for RegistryHeader {}
+
+    const HEADER_MAGIC: u32 = u32::from_le_bytes(*b"STID");
+    const HEADER_VERSION: u32 = 3;
+
+    #[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
+    unsafe extern "Rust" {
+        #[link_name = concat!("__start_smltidrs_small_type_id_rs", private::small_type_id_version!())]
+        static ENTRIES_START: MaybeUninit<private::TypeEntry>;
+        #[link_name = concat!("__stop_smltidrs_small_type_id_rs", private::small_type_id_version!())]
+        static ENTRIES_STOP: MaybeUninit<private::TypeEntry>;
+
+        #[link_name = "__start_smltidrs_registries"]
+        static REGISTRIES_START: *const RegistryHeader;
+        #[link_name = "__stop_smltidrs_registries"]
+        static REGISTRIES_STOP: *const RegistryHeader;
+    }
+
+    // Ensures that section exists even if there are no registered types
+    // so linker generates `__start_` and `__stop_` symbols.
+    #[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
+    #[unsafe(link_section = private::link_section_name!())]
+    #[used]
+    static AVOID_REMOVAL: MaybeUninit<private::TypeEntry> = MaybeUninit::zeroed();
+
+    // We use 1 item for the first element to avoid linking errors
+    // if there is no entries available.
+    #[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+    #[unsafe(link_section=concat!("smltidrs_small_type_id_rs$", private::small_type_id_version!(), "_a"))]
+    #[used]
+    static ENTRIES_START: [MaybeUninit<private::TypeEntry>; 1] = [MaybeUninit::zeroed()];
+    #[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+    #[unsafe(link_section=concat!("smltidrs_small_type_id_rs$", private::small_type_id_version!(), "_c"))]
+    #[used]
+    static ENTRIES_STOP: [MaybeUninit<private::TypeEntry>; 0] = [];
+
+    // Linker script sorts input sections by name so entries
+    // of every version stay between its own markers like on Windows.
+    #[cfg(feature = "linker_script_registration")]
+    #[unsafe(link_section=concat!(".small_type_id_registry.entries.", private::small_type_id_version!(), ".a"))]
+    #[used]
+    static ENTRIES_START: [MaybeUninit<private::TypeEntry>; 1] = [MaybeUninit::zeroed()];
+    #[cfg(feature = "linker_script_registration")]
+    #[unsafe(link_section=concat!(".small_type_id_registry.entries.", private::small_type_id_version!(), ".c"))]
+    #[used]
+    static ENTRIES_STOP: [MaybeUninit<private::TypeEntry>; 0] = [];
+
+    // Pointers to headers are bounded by symbols defined in linker script.
+    #[cfg(feature = "linker_script_registration")]
+    unsafe extern "Rust" {
+        #[link_name = "__small_type_id_registries_start"]
+        static REGISTRIES_START: *const RegistryHeader;
+        #[link_name = "__small_type_id_registries_end"]
+        static REGISTRIES_STOP: *const RegistryHeader;
+    }
+
+    // Every version defines its own start and stop markers.
+    // Since they are null, they are skipped as padding.
+    #[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+    #[unsafe(link_section = "smltidrs_registries$a")]
+    #[used]
+    static REGISTRIES_START: [Option<&RegistryHeader>; 1] = [None];
+    #[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+    #[unsafe(link_section = "smltidrs_registries$c")]
+    #[used]
+    static REGISTRIES_STOP: [Option<&RegistryHeader>; 0] = [];
+
+    static REGISTRY_HEADER: RegistryHeader = RegistryHeader {
+        magic: HEADER_MAGIC,
+        header_version: HEADER_VERSION,
+        header_size: size_of::<RegistryHeader>(),
+        small_type_id_version_ptr: env!("CARGO_PKG_VERSION").as_ptr(),
+        small_type_id_version_len: env!("CARGO_PKG_VERSION").len(),
+        #[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
+        entries_start: (&raw const ENTRIES_START).cast(),
+        #[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
+        entries_end: (&raw const ENTRIES_STOP).cast(),
+        #[cfg(any(target_os = "windows", feature = "linker_script_registration"))]
+        entries_start: (&raw const ENTRIES_START)
+            .cast::<MaybeUninit<private::TypeEntry>>()
+            .wrapping_add(1)
+            .cast(),
+        #[cfg(any(target_os = "windows", feature = "linker_script_registration"))]
+        entries_end: (&raw const ENTRIES_STOP).cast(),
+        entry_size: size_of::<private::TypeEntry>(),
+        entry_align: align_of::<private::TypeEntry>(),
+        type_id_offset: offset_of!(private::TypeEntry, type_id),
+        #[cfg(feature = "debug_type_name")]
+        type_name_offset: offset_of!(private::TypeEntry, type_name),
+        #[cfg(not(feature = "debug_type_name"))]
+        type_name_offset: usize::MAX,
+        sorted_ids,
+        #[cfg(feature = "debug_type_name")]
+        crate_version_offset: offset_of!(private::TypeEntry, crate_version),
+        #[cfg(not(feature = "debug_type_name"))]
+        crate_version_offset: usize::MAX,
+        #[cfg(feature = "debug_type_name")]
+        seed_offset: offset_of!(private::TypeEntry, seed),
+        #[cfg(not(feature = "debug_type_name"))]
+        seed_offset: usize::MAX,
+    };
+
+    #[cfg_attr(
+        all(target_os = "linux", not(feature = "linker_script_registration")),
+        unsafe(link_section = "smltidrs_registries")
+    )]
+    #[cfg_attr(
+        all(target_os = "windows", not(feature = "linker_script_registration")),
+        unsafe(link_section = "smltidrs_registries$b")
+    )]
+    #[cfg_attr(
+        feature = "linker_script_registration",
+        unsafe(link_section = ".small_type_id_registry.headers")
+    )]
+    #[used]
+    static REGISTRY_HEADER_REF: &RegistryHeader = &REGISTRY_HEADER;
+
+    #[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
+    unsafe extern "Rust" {
+        #[link_name = concat!("__start_smltidrs_ids", private::small_type_id_version!())]
+        static IDS_START: private::MirroredId;
+        #[link_name = concat!("__stop_smltidrs_ids", private::small_type_id_version!())]
+        static IDS_STOP: private::MirroredId;
+    }
+
+    // Zero values are skipped.
+    #[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
+    #[unsafe(link_section = private::ids_link_section_name!())]
+    #[used]
+    static AVOID_IDS_REMOVAL: private::MirroredId = private::MirroredId::zeroed();
+
+    #[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+    #[unsafe(link_section=concat!("smltidrs_ids$", private::small_type_id_version!(), "_a"))]
+    #[used]
+    static IDS_START: private::MirroredId = private::MirroredId::zeroed();
+    #[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+    #[unsafe(link_section=concat!("smltidrs_ids$", private::small_type_id_version!(), "_c"))]
+    #[used]
+    static IDS_STOP: [private::MirroredId; 0] = [];
+
+    // Ids are put into separate writable output section by linker script
+    // and sorted by name like entries.
+    #[cfg(feature = "linker_script_registration")]
+    #[unsafe(link_section=concat!(".small_type_id_ids.", private::small_type_id_version!(), ".a"))]
+    #[used]
+    static IDS_START: private::MirroredId = private::MirroredId::zeroed();
+    #[cfg(feature = "linker_script_registration")]
+    #[unsafe(link_section=concat!(".small_type_id_ids.", private::small_type_id_version!(), ".c"))]
+    #[used]
+    static IDS_STOP: [private::MirroredId; 0] = [];
+
+    const IDS_UNSORTED: u8 = 0;
+    const IDS_SORTING: u8 = 1;
+    const IDS_SORTED: u8 = 2;
+    static IDS_STATE: AtomicU8 = AtomicU8::new(IDS_UNSORTED);
+
+    /// Sorts ids in place on first call.
+    /// This allows to verify uniqueness in _O(n*log(n))_ time without allocations.
+    extern "C" fn sorted_ids(out_len: &mut usize) -> *const u32 {
+        // Use black_box to prevent provenance based code eliminations.
+        let start: *mut u32 = black_box((&raw const IDS_START).cast::<u32>().cast_mut());
+        let end: *mut u32 = black_box((&raw const IDS_STOP).cast::<u32>().cast_mut());
+        let len = (end.addr() - start.addr()) / size_of::<u32>();
+
+        match IDS_STATE.compare_exchange(IDS_UNSORTED, IDS_SORTING, Acquire, Acquire) {
+            Ok(_) => {
+                // SAFETY: Section contains only `MirroredId`s and zeroed padding.
+                // Values are modified only here, after we acquired exclusive access.
+                let ids = unsafe { core::slice::from_raw_parts_mut(start, len) };
+                ids.sort_unstable();
+                IDS_STATE.store(IDS_SORTED, Release);
+            }
+            Err(_) => {
+                while IDS_STATE.load(Acquire) != IDS_SORTED {
+                    core::hint::spin_loop();
+                }
             }
-            // Use black_box to prevent provenance based code eliminations.
-            let start_ptr = black_box(&raw const START);
-            let end_ptr = black_box(&raw const STOP);
+        }
 
-            (start_ptr, end_ptr)
-        };
+        // SAFETY: Values would never change after sorting.
+        let ids = unsafe { core::slice::from_raw_parts(start.cast_const(), len) };
+        // Skip zeroed padding.
+        let padding = ids.partition_point(|&x| x == 0);
+        let ids = &ids[padding..];
+        *out_len = ids.len();
+        ids.as_ptr()
+    }
 
-        // SAFETY: We can assume that all entries in link section are ours
-        // because our link section name is very specific (and sorting order includes our version)
-        // so other entries may appear only if someone insert them deliberately and using unsafe.
-        // We cannot really defend against this.
-        unsafe {
-            let mut it: *const u8 = start_ptr.cast();
-            core::iter::from_fn(move || {
-                // Skip padding that may be generated by incremental linker.
-                // See https://devblogs.microsoft.com/oldnewthing/20181109-00/?p=100175
-                // https://devblogs.microsoft.com/oldnewthing/20190114-00/?p=100695
-                while it != end_ptr.cast() {
-                    let first_val: u32 = *it.cast();
-                    if first_val != 0 {
+    /// Iterates over sorted ids of every version of `small_type_id` linked into binary.
+    ///
+    /// Returns `None` if some version doesn't provide sorted ids,
+    /// in such case entries must be checked directly.
+    pub(super) fn iter_sorted_ids() -> Option<impl Iterator<Item = &'static [u32]> + Clone> {
+        if iter_registries().any(|r| r.sorted_ids().is_none()) {
+            return None;
+        }
+        Some(iter_registries().filter_map(Registry::sorted_ids))
+    }
+
+    /// Iterates over items in link section skipping zeroed padding.
+    ///
+    /// # Safety
+    ///
+    /// Range must contain items of size `stride` aligned to `align`
+    /// or zeroed padding. Every item must have nonzero `u32` at `key_offset`.
+    unsafe fn iter_section(
+        start: *const u8,
+        end: *const u8,
+        stride: usize,
+        align: usize,
+        key_offset: usize,
+    ) -> impl Iterator<Item = *const u8> + Clone {
+        let mut it = start;
+        core::iter::from_fn(move || {
+            // Skip padding that may be generated by incremental linker.
+            // See https://devblogs.microsoft.com/oldnewthing/20181109-00/?p=100175
+            // https://devblogs.microsoft.com/oldnewthing/20190114-00/?p=100695
+            // SAFETY: Caller guarantees that range contain valid items or padding.
+            unsafe {
+                while it != end {
+                    let key: u32 = *it.add(key_offset).cast();
+                    if key != 0 {
                         break;
                     }
                     // This is a padding.
-                    it = it.add(align_of::<private::TypeEntry>());
+                    it = it.add(align);
                 }
-                if it == end_ptr.cast() {
+                if it == end {
                     None
                 } else {
-                    let p: *const private::TypeEntry = it.cast();
-                    it = it.add(size_of::<private::TypeEntry>());
-                    Some(&*p)
+                    let item = it;
+                    it = it.add(stride);
+                    Some(item)
                 }
+            }
+        })
+    }
+
+    /// Reads field of versioned struct put by some version of `small_type_id`
+    /// if struct is large enough to contain it.
+    macro_rules! read_field {
+        ($ptr:expr, $size:expr, $struct:ty, $name:ident: $t:ty) => {{
+            // Checks that `$t` is type of field.
+            let _: fn(&$struct) -> &$t = |h| &h.$name;
+            let offset = offset_of!($struct, $name);
+            if offset + size_of::<$t>() <= $size {
+                // SAFETY: Struct contains field and layout of existing fields never changes.
+                Some(unsafe { $ptr.byte_add(offset).cast::<$t>().read() })
+            } else {
+                None
+            }
+        }};
+    }
+
+    /// Iterates over non-null pointers in link section.
+    ///
+    /// # Safety
+    ///
+    /// Range must contain only pointers or null padding
+    /// that may be generated by incremental linker.
+    unsafe fn iter_pointers<T: 'static>(
+        start: *const *const T,
+        end: *const *const T,
+    ) -> impl Iterator<Item = *const T> + Clone {
+        // Use black_box to prevent provenance based code eliminations.
+        let start = black_box(start);
+        let end = black_box(end);
+        let len = (end.addr() - start.addr()) / size_of::<*const T>();
+        // SAFETY: Caller guarantees that range contains only pointers.
+        let pointers = unsafe { core::slice::from_raw_parts(start, len) };
+        pointers.iter().copied().filter(|p| !p.is_null())
+    }
+
+    /// Fields of header put by some version of `small_type_id`.
+    ///
+    /// Fields missing in headers put by older versions are replaced by defaults.
+    #[derive(Clone, Copy)]
+    pub(super) struct Registry {
+        entries_start: *const u8,
+        entries_end: *const u8,
+        entry_size: usize,
+        entry_align: usize,
+        type_id_offset: usize,
+        #[cfg_attr(not(feature = "debug_type_name"), allow(dead_code))]
+        type_name_offset: usize,
+        sorted_ids: Option<extern "C" fn(&mut usize) -> *const u32>,
+        #[cfg_attr(not(feature = "debug_type_name"), allow(dead_code))]
+        crate_version_offset: usize,
+        #[cfg_attr(not(feature = "debug_type_name"), allow(dead_code))]
+        seed_offset: usize,
+    }
+
+    impl Registry {
+        /// Reads header or returns `None` if it is not header of some version of `small_type_id`.
+        ///
+        /// # Safety
+        ///
+        /// `header` must point to header put by some version of `small_type_id`,
+        /// which may be smaller or larger than [`RegistryHeader`] of this version.
+        unsafe fn read(header: *const RegistryHeader) -> Option<Self> {
+            // SAFETY: Fields `magic` and `header_size` are present in every version.
+            let (magic, header_size) = unsafe {
+                (
+                    header
+                        .byte_add(offset_of!(RegistryHeader, magic))
+                        .cast::<u32>()
+                        .read(),
+                    header
+                        .byte_add(offset_of!(RegistryHeader, header_size))
+                        .cast::<usize>()
+                        .read(),
+                )
+            };
+            if magic != HEADER_MAGIC {
+                return None;
+            }
+            macro_rules! field {
+                ($name:ident: $t:ty) => {
+                    read_field!(header, header_size, RegistryHeader, $name: $t)
+                };
+            }
+            Some(Self {
+                entries_start: field!(entries_start: *const u8)?,
+                entries_end: field!(entries_end: *const u8)?,
+                entry_size: field!(entry_size: usize)?,
+                entry_align: field!(entry_align: usize)?,
+                type_id_offset: field!(type_id_offset: usize)?,
+                type_name_offset: field!(type_name_offset: usize)?,
+                sorted_ids: field!(sorted_ids: extern "C" fn(&mut usize) -> *const u32),
+                crate_version_offset: field!(crate_version_offset: usize).unwrap_or(usize::MAX),
+                seed_offset: field!(seed_offset: usize).unwrap_or(usize::MAX),
             })
         }
     }
 
-    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
-    fn check_registered_entries() {
-        let mut buffer = [0_u32; 2048];
-        let mut it = iter_registered_types();
-        loop {
-            #[cfg(feature = "debug_type_name")]
-            let original_start = it.clone();
+    /// Iterates over headers of every version of `small_type_id` that put its header.
+    pub(super) fn iter_registries() -> impl Iterator<Item = Registry> + Clone {
+        #[cfg(any(target_os = "linux", feature = "linker_script_registration"))]
+        let (start_ptr, end_ptr): (
+            *const *const RegistryHeader,
+            *const *const RegistryHeader,
+        ) = (&raw const REGISTRIES_START, &raw const REGISTRIES_STOP);
+        #[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+        let (start_ptr, end_ptr): (
+            *const *const RegistryHeader,
+            *const *const RegistryHeader,
+        ) = (
+            REGISTRIES_START.as_ptr().wrapping_add(1).cast(),
+            REGISTRIES_STOP.as_ptr().cast(),
+        );
+        // SAFETY: Section contains only pointers to headers put there by versions of this crate
+        // because our link section name is very specific.
+        unsafe { iter_pointers(start_ptr, end_ptr) }.filter_map(|p| unsafe { Registry::read(p) })
+    }
 
+    impl Registry {
+        /// Returns sorted ids of entries if version that put header provides them.
+        fn sorted_ids(self) -> Option<&'static [u32]> {
             let mut len = 0;
-            for entry in it.by_ref() {
-                buffer[len] = entry.type_id.as_u32();
-                len += 1;
-                if len == buffer.len() {
-                    break;
-                }
-            }
-            if len == 0 {
-                break;
-            }
-            let known_types = &mut buffer[..len];
-
-            known_types.sort_unstable();
-
-            for w in known_types.windows(2) {
-                let [a, b] = w[..] else { unreachable!() };
-                if a == b {
-                    let tid = TypeId(NonZeroU32::new(a).unwrap());
-                    handle_duplicate_typeid(
-                        tid,
-                        #[cfg(feature = "debug_type_name")]
-                        original_start,
-                    );
-                }
-            }
+            let ids = (self.sorted_ids?)(&mut len);
+            // SAFETY: Non-null pointer points to `len` sorted ids which never change.
+            (!ids.is_null()).then(|| unsafe { core::slice::from_raw_parts(ids, len) })
+        }
 
-            for t in it.clone() {
-                let inner = t.type_id.as_u32();
-                if known_types.binary_search(&inner).is_ok() {
-                    handle_duplicate_typeid(
-                        t.type_id,
-                        #[cfg(feature = "debug_type_name")]
-                        original_start,
-                    );
+        pub(super) fn iter_entries(self) -> impl Iterator<Item = crate::TypeEntry> + Clone {
+            let type_id_offset = self.type_id_offset;
+            #[cfg(feature = "debug_type_name")]
+            let type_name_offset = self.type_name_offset;
+            #[cfg(feature = "debug_type_name")]
+            let (crate_version_offset, seed_offset) = (self.crate_version_offset, self.seed_offset);
+            // SAFETY: We can assume that all entries in link section are ours
+            // because our link section name is very specific (and sorting order includes our version)
+            // so other entries may appear only if someone insert them deliberately and using unsafe.
+            // We cannot really defend against this.
+            let entries = unsafe {
+                iter_section(
+                    black_box(self.entries_start),
+                    black_box(self.entries_end),
+                    self.entry_size,
+                    self.entry_align,
+                    type_id_offset,
+                )
+            };
+            entries.map(move |p| unsafe {
+                let type_id: u32 = *p.add(type_id_offset).cast();
+                crate::TypeEntry {
+                    type_id: TypeId(NonZeroU32::new_unchecked(type_id)),
+                    #[cfg(feature = "debug_type_name")]
+                    debug_type_name: if type_name_offset == usize::MAX {
+                        UNKNOWN_TYPE_NAME
+                    } else {
+                        let name: *const &'static str = p.add(type_name_offset).cast();
+                        *name
+                    },
+                    #[cfg(feature = "debug_type_name")]
+                    debug_crate_version: if crate_version_offset == usize::MAX {
+                        ""
+                    } else {
+                        let version: *const &'static str = p.add(crate_version_offset).cast();
+                        *version
+                    },
+                    #[cfg(feature = "debug_type_name")]
+                    debug_seed: if seed_offset == usize::MAX {
+                        0
+                    } else {
+                        let seed: *const u32 = p.add(seed_offset).cast();
+                        *seed
+                    },
                 }
-            }
+            })
+        }
+    }
+
+    #[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    unsafe extern "Rust" {
+        #[link_name = "__start_smltidrs_collision_policies"]
+        static POLICIES_START: *const private::CollisionPolicyRecord;
+        #[link_name = "__stop_smltidrs_collision_policies"]
+        static POLICIES_STOP: *const private::CollisionPolicyRecord;
+    }
+
+    // Ensures that section exists even if policy is not selected.
+    #[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    #[unsafe(link_section = private::collision_policy_section_name!())]
+    #[used]
+    static AVOID_POLICIES_REMOVAL: Option<&private::CollisionPolicyRecord> = None;
+
+    #[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    #[unsafe(link_section = "smltidrs_collision_policies$a")]
+    #[used]
+    static POLICIES_START: [Option<&private::CollisionPolicyRecord>; 1] = [None];
+    #[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    #[unsafe(link_section = "smltidrs_collision_policies$c")]
+    #[used]
+    static POLICIES_STOP: [Option<&private::CollisionPolicyRecord>; 0] = [];
+
+    #[cfg(feature = "linker_script_registration")]
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    unsafe extern "Rust" {
+        #[link_name = "__small_type_id_policies_start"]
+        static POLICIES_START: *const private::CollisionPolicyRecord;
+        #[link_name = "__small_type_id_policies_end"]
+        static POLICIES_STOP: *const private::CollisionPolicyRecord;
+    }
+
+    /// Reads policy from record put by some version of `small_type_id`.
+    ///
+    /// Hook selected using other version cannot be called
+    /// so the safest policy is used instead.
+    ///
+    /// # Safety
+    ///
+    /// `record` must point to record put by some version of `small_type_id`,
+    /// which may be smaller or larger than [`private::CollisionPolicyRecord`] of this version.
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    unsafe fn read_policy(
+        record: *const private::CollisionPolicyRecord,
+    ) -> Option<CollisionPolicy> {
+        type Record = private::CollisionPolicyRecord;
+        // SAFETY: Fields `magic` and `record_size` are present in every version.
+        let (magic, record_size) = unsafe {
+            (
+                record
+                    .byte_add(offset_of!(Record, magic))
+                    .cast::<u32>()
+                    .read(),
+                record
+                    .byte_add(offset_of!(Record, record_size))
+                    .cast::<usize>()
+                    .read(),
+            )
+        };
+        if magic != COLLISION_POLICY_MAGIC {
+            return None;
+        }
+        macro_rules! field {
+            ($name:ident: $t:ty) => {
+                read_field!(record, record_size, Record, $name: $t)
+            };
+        }
+        let version_ptr = field!(small_type_id_version_ptr: *const u8)?;
+        let version_len = field!(small_type_id_version_len: usize)?;
+        // SAFETY: Every version points to its version string.
+        let version = unsafe { core::slice::from_raw_parts(version_ptr, version_len) };
+        if version == env!("CARGO_PKG_VERSION").as_bytes() {
+            return field!(policy: CollisionPolicy);
+        }
+        Some(match field!(kind: u32)? {
+            POLICY_KIND_PANIC => CollisionPolicy::Panic,
+            POLICY_KIND_WARN => CollisionPolicy::Warn,
+            _ => CollisionPolicy::Abort,
+        })
+    }
+
+    /// Returns policy put into link section by [`collision_policy!`](crate::collision_policy)
+    /// using any version of `small_type_id` linked into binary.
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    pub(super) fn registered_collision_policy() -> Option<CollisionPolicy> {
+        #[cfg(any(target_os = "linux", feature = "linker_script_registration"))]
+        let (start_ptr, end_ptr): (
+            *const *const private::CollisionPolicyRecord,
+            *const *const private::CollisionPolicyRecord,
+        ) = (&raw const POLICIES_START, &raw const POLICIES_STOP);
+        #[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+        let (start_ptr, end_ptr): (
+            *const *const private::CollisionPolicyRecord,
+            *const *const private::CollisionPolicyRecord,
+        ) = (
+            POLICIES_START.as_ptr().wrapping_add(1).cast(),
+            POLICIES_STOP.as_ptr().cast(),
+        );
+
+        // SAFETY: Only `collision_policy!` macro of some version puts pointers into this section.
+        let mut policies =
+            unsafe { iter_pointers(start_ptr, end_ptr) }.filter_map(|p| unsafe { read_policy(p) });
+        let first = policies.next()?;
+        if policies.next().is_some() {
+            // Policy selected more than once, we cannot know which one is correct
+            // so we use the safest one.
+            return Some(CollisionPolicy::Abort);
+        }
+        Some(first)
+    }
+
+    /// Iterates over types registered by every version of `small_type_id` linked into binary.
+    pub(super) fn iter_registered_types() -> impl Iterator<Item = crate::TypeEntry> + Clone {
+        iter_registries().flat_map(Registry::iter_entries)
+    }
+
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    pub(super) fn check_registered_entries() {
+        if has_duplicate_type_ids() {
+            handle_duplicate_typeids();
         }
     }
 
     // Note `check_registered_entries` is not inside
     // because `cargo fmt` often fails with declarative macro invokations.
     #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    #[cfg(not(any(feature = "lazy_verification", feature = "linker_script_registration")))]
     ctor::declarative::ctor! {
         #[ctor]
         unsafe fn check_registered_entries_(){
             check_registered_entries();
         }
     }
+
+    // Linker script doesn't prevent running constructors on targets with operating system
+    // so verification runs before `main` there too and later calls do nothing.
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    #[cfg(all(
+        feature = "linker_script_registration",
+        not(feature = "lazy_verification"),
+        not(target_os = "none")
+    ))]
+    ctor::declarative::ctor! {
+        #[ctor]
+        unsafe fn ensure_verified_(){
+            super::ensure_verified();
+        }
+    }
+
+    // MIRI unsupported until https://github.com/rust-lang/miri/issues/450 fixed
+    #[cfg(all(test, not(miri)))]
+    #[allow(clippy::wildcard_imports)]
+    mod tests {
+        use super::*;
+
+        /// Emulates entries of some other version of crate with different layout.
+        #[repr(C)]
+        struct ForeignEntry {
+            type_name: &'static str,
+            seed: u32,
+            type_id: u32,
+        }
+
+        static FOREIGN_ENTRIES: [ForeignEntry; 2] = [
+            ForeignEntry {
+                type_name: "foreign::First",
+                seed: 0,
+                type_id: 0x7ABC_0001,
+            },
+            ForeignEntry {
+                type_name: "foreign::Second",
+                seed: 5,
+                type_id: 0x7ABC_0002,
+            },
+        ];
+
+        extern "C" fn foreign_sorted_ids(len: &mut usize) -> *const u32 {
+            static IDS: [u32; 2] = [0x7ABC_0001, 0x7ABC_0002];
+            *len = IDS.len();
+            IDS.as_ptr()
+        }
+
+        /// Emulates header of newer version that appended some field.
+        #[repr(C)]
+        struct NewerHeader {
+            header: RegistryHeader,
+            appended: u64,
+        }
+
+        // SAFETY: Same as for `RegistryHeader`.
+        unsafe impl Sync for NewerHeader {}
+
+        static FOREIGN_HEADER: NewerHeader = NewerHeader {
+            header: RegistryHeader {
+                magic: HEADER_MAGIC,
+                header_version: HEADER_VERSION + 1,
+                header_size: size_of::<NewerHeader>(),
+                small_type_id_version_ptr: "999.0.0".as_ptr(),
+                small_type_id_version_len: "999.0.0".len(),
+                entries_start: FOREIGN_ENTRIES.as_ptr().cast(),
+                entries_end: FOREIGN_ENTRIES.as_ptr().wrapping_add(2).cast(),
+                entry_size: size_of::<ForeignEntry>(),
+                entry_align: align_of::<ForeignEntry>(),
+                type_id_offset: offset_of!(ForeignEntry, type_id),
+                type_name_offset: offset_of!(ForeignEntry, type_name),
+                sorted_ids: foreign_sorted_ids,
+                crate_version_offset: usize::MAX,
+                seed_offset: offset_of!(ForeignEntry, seed),
+            },
+            appended: u64::MAX,
+        };
+
+        static OLD_ENTRIES: [ForeignEntry; 1] = [ForeignEntry {
+            type_name: "old::Only",
+            seed: 7,
+            type_id: 0x7ABD_0001,
+        }];
+
+        /// Emulates header of version 1 which had no sorted ids,
+        /// crate versions and seeds.
+        #[repr(C)]
+        struct HeaderV1 {
+            magic: u32,
+            header_version: u32,
+            header_size: usize,
+            small_type_id_version_ptr: *const u8,
+            small_type_id_version_len: usize,
+            entries_start: *const u8,
+            entries_end: *const u8,
+            entry_size: usize,
+            entry_align: usize,
+            type_id_offset: usize,
+            type_name_offset: usize,
+        }
+
+        // SAFETY: Same as for `RegistryHeader`.
+        unsafe impl Sync for HeaderV1 {}
+
+        static OLD_HEADER: HeaderV1 = HeaderV1 {
+            magic: HEADER_MAGIC,
+            header_version: 1,
+            header_size: size_of::<HeaderV1>(),
+            small_type_id_version_ptr: "0.1.0".as_ptr(),
+            small_type_id_version_len: "0.1.0".len(),
+            entries_start: OLD_ENTRIES.as_ptr().cast(),
+            entries_end: OLD_ENTRIES.as_ptr().wrapping_add(1).cast(),
+            entry_size: size_of::<ForeignEntry>(),
+            entry_align: align_of::<ForeignEntry>(),
+            type_id_offset: offset_of!(ForeignEntry, type_id),
+            type_name_offset: offset_of!(ForeignEntry, type_name),
+        };
+
+        #[cfg_attr(
+            all(target_os = "linux", not(feature = "linker_script_registration")),
+            unsafe(link_section = "smltidrs_registries")
+        )]
+        #[cfg_attr(
+            all(target_os = "windows", not(feature = "linker_script_registration")),
+            unsafe(link_section = "smltidrs_registries$b")
+        )]
+        #[cfg_attr(
+            feature = "linker_script_registration",
+            unsafe(link_section = ".small_type_id_registry.headers")
+        )]
+        #[used]
+        static FOREIGN_HEADER_REF: &NewerHeader = &FOREIGN_HEADER;
+        #[cfg_attr(
+            all(target_os = "linux", not(feature = "linker_script_registration")),
+            unsafe(link_section = "smltidrs_registries")
+        )]
+        #[cfg_attr(
+            all(target_os = "windows", not(feature = "linker_script_registration")),
+            unsafe(link_section = "smltidrs_registries$b")
+        )]
+        #[cfg_attr(
+            feature = "linker_script_registration",
+            unsafe(link_section = ".small_type_id_registry.headers")
+        )]
+        #[used]
+        static OLD_HEADER_REF: &HeaderV1 = &OLD_HEADER;
+
+        #[test]
+        fn layout_of_old_fields_is_kept() {
+            assert_eq!(
+                offset_of!(HeaderV1, type_name_offset),
+                offset_of!(RegistryHeader, type_name_offset)
+            );
+            assert!(size_of::<HeaderV1>() <= offset_of!(RegistryHeader, sorted_ids));
+        }
+
+        #[test]
+        fn finds_all_headers() {
+            let registries: Vec<Registry> = iter_registries().collect();
+            assert_eq!(registries.len(), 3);
+            for entries_start in [
+                REGISTRY_HEADER.entries_start,
+                FOREIGN_HEADER.header.entries_start,
+                OLD_HEADER.entries_start,
+            ] {
+                assert!(registries.iter().any(|r| r.entries_start == entries_start));
+            }
+            let old = registries
+                .iter()
+                .find(|r| r.entries_start == OLD_HEADER.entries_start)
+                .unwrap();
+            assert!(old.sorted_ids.is_none());
+            assert_eq!(old.crate_version_offset, usize::MAX);
+            assert_eq!(old.seed_offset, usize::MAX);
+        }
+
+        #[test]
+        fn sorts_ids() {
+            let sorted: Vec<&[u32]> = iter_registries().filter_map(Registry::sorted_ids).collect();
+            assert_eq!(sorted.len(), 2);
+            assert!(sorted.contains(&&[0x7ABC_0001, 0x7ABC_0002][..]));
+            assert!(sorted.iter().all(|s| s.is_sorted() && !s.contains(&0)));
+            assert!(!duplicates::has_sorted_duplicates(sorted.iter().copied()));
+
+            let mut ids: Vec<u32> = crate::iter_registered_types()
+                .map(|e| e.type_id.as_u32())
+                .filter(|&id| id != 0x7ABD_0001)
+                .collect();
+            ids.sort_unstable();
+            let mut merged: Vec<u32> = sorted.concat();
+            merged.sort_unstable();
+            assert_eq!(ids, merged);
+        }
+
+        #[test]
+        fn checks_entries_of_versions_without_sorted_ids() {
+            assert!(iter_sorted_ids().is_none());
+            assert!(!has_duplicate_type_ids());
+            assert_eq!(iter_duplicate_type_ids().count(), 0);
+        }
+
+        #[test]
+        fn iterates_entries_of_other_versions() {
+            let foreign: Vec<crate::TypeEntry> = crate::iter_registered_types()
+                .filter(|e| e.type_id.as_u32() & 0xFFFF_0000 == 0x7ABC_0000)
+                .collect();
+            assert_eq!(foreign.len(), 2);
+            assert_eq!(foreign[0].type_id.as_u32(), 0x7ABC_0001);
+            assert_eq!(foreign[1].type_id.as_u32(), 0x7ABC_0002);
+            #[cfg(feature = "debug_type_name")]
+            {
+                assert_eq!(foreign[0].debug_type_name, "foreign::First");
+                assert_eq!(foreign[1].debug_type_name, "foreign::Second");
+                assert_eq!(foreign[0].debug_crate_version, "");
+                assert_eq!(foreign[1].debug_seed, 5);
+            }
+
+            let old: Vec<crate::TypeEntry> = crate::iter_registered_types()
+                .filter(|e| e.type_id.as_u32() == 0x7ABD_0001)
+                .collect();
+            assert_eq!(old.len(), 1);
+            #[cfg(feature = "debug_type_name")]
+            {
+                assert_eq!(old[0].debug_type_name, "old::Only");
+                // Seed is not read because version 1 header doesn't have its offset.
+                assert_eq!(old[0].debug_seed, 0);
+            }
+        }
+
+        /// Emulates policy record of other version
+        /// with different layout of policy and appended field.
+        #[repr(C)]
+        struct ForeignPolicyRecord {
+            magic: u32,
+            record_version: u32,
+            record_size: usize,
+            small_type_id_version_ptr: *const u8,
+            small_type_id_version_len: usize,
+            kind: u32,
+            policy: [usize; 3],
+            appended: u64,
+        }
+
+        // SAFETY: Same as for `CollisionPolicyRecord`.
+        unsafe impl Sync for ForeignPolicyRecord {}
+
+        const fn foreign_policy(kind: u32) -> ForeignPolicyRecord {
+            ForeignPolicyRecord {
+                magic: COLLISION_POLICY_MAGIC,
+                record_version: POLICY_RECORD_VERSION + 1,
+                record_size: size_of::<ForeignPolicyRecord>(),
+                small_type_id_version_ptr: "99.0.0".as_ptr(),
+                small_type_id_version_len: "99.0.0".len(),
+                kind,
+                policy: [usize::MAX; 3],
+                appended: 0,
+            }
+        }
+
+        static FOREIGN_HOOK_POLICY: ForeignPolicyRecord = foreign_policy(POLICY_KIND_HOOK);
+
+        // Nothing in this binary has duplicate ids so policy is never applied.
+        #[cfg_attr(
+            all(target_os = "linux", not(feature = "linker_script_registration")),
+            unsafe(link_section = "smltidrs_collision_policies")
+        )]
+        #[cfg_attr(
+            all(target_os = "windows", not(feature = "linker_script_registration")),
+            unsafe(link_section = "smltidrs_collision_policies$b")
+        )]
+        #[cfg_attr(
+            feature = "linker_script_registration",
+            unsafe(link_section = ".small_type_id_registry.policies")
+        )]
+        #[used]
+        static FOREIGN_POLICY_REF: &ForeignPolicyRecord = &FOREIGN_HOOK_POLICY;
+
+        #[test]
+        fn layout_of_policy_record_is_kept() {
+            type Record = private::CollisionPolicyRecord;
+            assert_eq!(
+                offset_of!(ForeignPolicyRecord, record_size),
+                offset_of!(Record, record_size)
+            );
+            assert_eq!(
+                offset_of!(ForeignPolicyRecord, kind),
+                offset_of!(Record, kind)
+            );
+            assert_eq!(
+                offset_of!(ForeignPolicyRecord, policy),
+                offset_of!(Record, policy)
+            );
+        }
+
+        #[test]
+        #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+        fn reads_policies_of_other_versions() {
+            let read =
+                |r: &ForeignPolicyRecord| unsafe { read_policy(core::ptr::from_ref(r).cast()) };
+            assert!(matches!(
+                read(&foreign_policy(POLICY_KIND_WARN)),
+                Some(CollisionPolicy::Warn)
+            ));
+            assert!(matches!(
+                read(&foreign_policy(POLICY_KIND_PANIC)),
+                Some(CollisionPolicy::Panic)
+            ));
+            // Hooks of other versions cannot be called.
+            assert!(matches!(
+                read(&FOREIGN_HOOK_POLICY),
+                Some(CollisionPolicy::Abort)
+            ));
+            assert!(matches!(
+                registered_collision_policy(),
+                Some(CollisionPolicy::Abort)
+            ));
+
+            fn hook(_: &CollisionReport) {}
+            let own = private::CollisionPolicyRecord::new(CollisionPolicy::Hook(hook));
+            assert!(matches!(
+                unsafe { read_policy(&raw const own) },
+                Some(CollisionPolicy::Hook(_))
+            ));
+        }
+    }
 }
 
-#[cfg(not(feature = "unsafe_dont_register_types"))]
-#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+#[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+#[cfg(not(small_type_id_link_section))]
 mod with_ctors_per_entry {
-    use core::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    use core::cell::UnsafeCell;
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    use core::sync::atomic::AtomicBool;
+    use core::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
 
     #[allow(clippy::wildcard_imports)]
     use super::*;
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    use crate::skip_list::{InsertResult, SkipList};
+
+    /// Allows to handle 2<sup>16</sup> types without degradation of performance.
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    pub(super) const SKIP_LIST_HEIGHT: usize = 16;
+
+    /// Contains all registered entries sorted by [`TypeId`].
+    ///
+    /// Unlike [`LAST_ADDED_TYPE`], it cannot be read without locking
+    /// so it is used only to find duplicates.
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    struct LockedSkipList {
+        locked: AtomicBool,
+        list: UnsafeCell<SkipList<'static, TypeId, SKIP_LIST_HEIGHT>>,
+    }
+
+    // SAFETY: `list` is accessed only while `locked` is held.
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    unsafe impl Sync for LockedSkipList {}
+
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    static SORTED_TYPES: LockedSkipList = LockedSkipList {
+        locked: AtomicBool::new(false),
+        list: UnsafeCell::new(SkipList::new()),
+    };
 
     static LAST_ADDED_TYPE: AtomicPtr<private::TypeEntry> = AtomicPtr::new(ptr::null_mut());
 
+    static COLLISION_POLICY: AtomicPtr<private::CollisionPolicyRecord> =
+        AtomicPtr::new(ptr::null_mut());
+
+    /// Dangling pointer that marks that policy is selected more than once.
+    const CONFLICTING_POLICIES: *mut private::CollisionPolicyRecord = ptr::dangling_mut();
+
+    pub(super) fn set_collision_policy(record: &'static private::CollisionPolicyRecord) {
+        let p: *mut private::CollisionPolicyRecord = ptr::from_ref(record).cast_mut();
+        if COLLISION_POLICY
+            .compare_exchange(ptr::null_mut(), p, AcqRel, Acquire)
+            .is_err()
+        {
+            COLLISION_POLICY.store(CONFLICTING_POLICIES, Release);
+        }
+    }
+
+    /// Returns policy set by [`collision_policy!`](crate::collision_policy).
+    ///
+    /// Since order of constructors is not specified,
+    /// policy may be not set yet when duplicates are found.
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    pub(super) fn registered_collision_policy() -> Option<CollisionPolicy> {
+        let p = COLLISION_POLICY.load(Acquire);
+        if p == CONFLICTING_POLICIES {
+            // Policy selected more than once, we cannot know which one is correct
+            // so we use the safest one.
+            return Some(CollisionPolicy::Abort);
+        }
+        // SAFETY: Only valid static references are stored.
+        unsafe { p.as_ref() }.map(|r| r.policy)
+    }
+
     pub(super) fn iter_registered_types()
     -> impl Iterator<Item = &'static private::TypeEntry> + Clone {
         let mut current = LAST_ADDED_TYPE.load(Acquire);
@@ -299,83 +1445,278 @@ mod with_ctors_per_entry {
                 Err(p) => next = p,
             }
         }
+    }
 
-        // This code tests that we don't have registered any duplicates.
-        // Unfortunately, it runs in quadratic time.
-        #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
-        check_for_duplicates_of_first();
+    /// Tests that we don't have registered any duplicates.
+    ///
+    /// Must be called after registration of entry with same [`TypeId`]
+    /// so report would contain all duplicates registered so far.
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    pub(super) unsafe fn check_type<const HEIGHT: usize>(node: &'static private::TypeNode<HEIGHT>) {
+        if insert_into_sorted(node) {
+            #[cfg(feature = "lazy_verification")]
+            if !lazy_verification::defer_duplicates_handling() {
+                return;
+            }
+            handle_duplicate_typeids();
+        }
     }
 
+    /// Inserts node into skip list in _O(log(n))_ time.
+    /// Returns true if there is already node with same [`TypeId`].
     #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
-    fn check_for_duplicates_of_first() {
-        let mut it = iter_registered_types();
-        let first_entry = it.next().unwrap();
-        let type_id = first_entry.type_id;
-        for entry in it {
-            if type_id == entry.type_id {
-                handle_duplicate_typeid(
-                    type_id,
-                    #[cfg(feature = "debug_type_name")]
-                    iter_registered_types(),
-                );
+    fn insert_into_sorted<const HEIGHT: usize>(node: &'static private::TypeNode<HEIGHT>) -> bool {
+        while SORTED_TYPES
+            .locked
+            .compare_exchange_weak(false, true, Acquire, Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+
+        // SAFETY: We hold the lock.
+        // Every node is registered only once by its constructor
+        // so nobody else has access to it.
+        // Height of node is already chosen randomly by `node_height`.
+        let res = unsafe {
+            let list = &mut *SORTED_TYPES.list.get();
+            list.insert_full_tower(&mut *node.0.get())
+        };
+
+        SORTED_TYPES.locked.store(false, Release);
+        matches!(res, InsertResult::Duplicate(_))
+    }
+}
+
+#[cfg(not(feature = "unsafe_dont_register_types"))]
+#[cfg(feature = "manual_registry")]
+mod manual_registry {
+    #[allow(clippy::wildcard_imports)]
+    use super::*;
+
+    unsafe extern "Rust" {
+        /// Defined by [`registry!`](crate::registry) macro in final binary.
+        #[link_name = concat!("small_type_id_manual_registry_", private::small_type_id_version!())]
+        safe fn manual_registry() -> &'static private::ManualRegistry;
+    }
+
+    pub(super) fn iter_registered_types() -> impl Iterator<Item = crate::TypeEntry> + Clone {
+        manual_registry().entries.iter().copied()
+    }
+
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    pub(super) fn registered_collision_policy() -> Option<CollisionPolicy> {
+        manual_registry().policy
+    }
+}
+
+/// Runs verification if it is deferred until first use.
+pub(crate) fn ensure_verified() {
+    #[cfg(any(
+        feature = "lazy_verification",
+        feature = "manual_registry",
+        all(small_type_id_link_section, feature = "linker_script_registration")
+    ))]
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    lazy_verification::ensure_verified();
+}
+
+#[cfg(any(
+    feature = "lazy_verification",
+    feature = "manual_registry",
+    all(small_type_id_link_section, feature = "linker_script_registration")
+))]
+#[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+mod lazy_verification {
+    #[cfg(not(feature = "manual_registry"))]
+    #[cfg(not(small_type_id_link_section))]
+    use core::sync::atomic::AtomicBool;
+    use core::sync::atomic::AtomicU8;
+    use core::sync::atomic::Ordering::{Acquire, Release, SeqCst};
+
+    #[allow(clippy::wildcard_imports)]
+    use super::*;
+
+    const NOT_VERIFIED: u8 = 0;
+    const VERIFYING: u8 = 1;
+    const VERIFIED: u8 = 2;
+    static STATE: AtomicU8 = AtomicU8::new(NOT_VERIFIED);
+
+    /// Set by constructors which found duplicates before verification.
+    #[cfg(not(feature = "manual_registry"))]
+    #[cfg(not(small_type_id_link_section))]
+    static HAS_DUPLICATES: AtomicBool = AtomicBool::new(false);
+
+    pub(super) fn ensure_verified() {
+        if STATE.load(Acquire) == VERIFIED {
+            return;
+        }
+        match STATE.compare_exchange(NOT_VERIFIED, VERIFYING, SeqCst, Acquire) {
+            Ok(_) => {
+                verify();
+                STATE.store(VERIFIED, Release);
             }
+            Err(_) => {
+                // Other thread verifies entries right now.
+                while STATE.load(Acquire) != VERIFIED {
+                    core::hint::spin_loop();
+                }
+            }
+        }
+    }
+
+    fn verify() {
+        #[cfg(feature = "manual_registry")]
+        if has_duplicate_type_ids() {
+            handle_duplicate_typeids();
+        }
+        #[cfg(not(feature = "manual_registry"))]
+        #[cfg(small_type_id_link_section)]
+        with_link_section::check_registered_entries();
+        #[cfg(not(feature = "manual_registry"))]
+        #[cfg(not(small_type_id_link_section))]
+        if HAS_DUPLICATES.load(SeqCst) {
+            handle_duplicate_typeids();
         }
     }
+
+    /// Called when constructor finds duplicates.
+    /// Returns true if they must be handled immediately because verification already started.
+    #[cfg(not(feature = "manual_registry"))]
+    #[cfg(not(small_type_id_link_section))]
+    pub(super) fn defer_duplicates_handling() -> bool {
+        HAS_DUPLICATES.store(true, SeqCst);
+        STATE.load(SeqCst) != NOT_VERIFIED
+    }
 }
 
 #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
 #[cfg_attr(windows, path = "win.rs")]
-#[cfg_attr(unix, path = "unix.rs")]
+#[cfg_attr(
+    all(unix, not(all(target_os = "linux", feature = "linux_raw_syscalls"))),
+    path = "unix.rs"
+)]
+#[cfg_attr(
+    all(target_os = "linux", feature = "linux_raw_syscalls"),
+    path = "linux.rs"
+)]
+#[cfg_attr(not(any(unix, windows)), path = "no_os.rs")]
 mod platform;
 
+/// Iterates over every [`TypeId`] shared by multiple registered types once.
+pub(crate) fn iter_duplicate_type_ids() -> impl Iterator<Item = TypeId> {
+    #[cfg(feature = "unsafe_dont_register_types")]
+    let duplicates = core::iter::empty();
+    #[cfg(not(feature = "unsafe_dont_register_types"))]
+    #[cfg(feature = "manual_registry")]
+    let duplicates = duplicates::ChunkedDuplicates::new(
+        manual_registry::iter_registered_types().map(|e| e.type_id.as_u32()),
+    );
+    #[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+    #[cfg(not(small_type_id_link_section))]
+    let duplicates = duplicates::ChunkedDuplicates::new(
+        with_ctors_per_entry::iter_registered_types().map(|e| e.type_id.as_u32()),
+    );
+    #[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+    #[cfg(small_type_id_link_section)]
+    let duplicates = {
+        let sorted = with_link_section::iter_sorted_ids();
+        // Entries are checked directly only if some version doesn't provide sorted ids.
+        let chunked = sorted.is_none().then(|| {
+            duplicates::ChunkedDuplicates::new(
+                with_link_section::iter_registered_types().map(|e| e.type_id.as_u32()),
+            )
+        });
+        let sorted = sorted.map(duplicates::SortedDuplicates::new);
+        sorted
+            .into_iter()
+            .flatten()
+            .chain(chunked.into_iter().flatten())
+    };
+
+    duplicates.map(|id| TypeId(NonZeroU32::new(id).unwrap()))
+}
+
+/// Uses same algorithm as automatic verification on Windows and Linux.
+pub(crate) fn has_duplicate_type_ids() -> bool {
+    #[cfg(feature = "unsafe_dont_register_types")]
+    let res = false;
+    #[cfg(not(feature = "unsafe_dont_register_types"))]
+    #[cfg(feature = "manual_registry")]
+    let res = iter_duplicate_type_ids().next().is_some();
+    #[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+    #[cfg(not(small_type_id_link_section))]
+    let res = iter_duplicate_type_ids().next().is_some();
+    #[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+    #[cfg(small_type_id_link_section)]
+    let res = match with_link_section::iter_sorted_ids() {
+        Some(sorted) => duplicates::has_sorted_duplicates(sorted),
+        None => iter_duplicate_type_ids().next().is_some(),
+    };
+
+    res
+}
+
+/// Returns policy selected by binary using [`collision_policy!`](crate::collision_policy).
+#[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+fn current_collision_policy() -> CollisionPolicy {
+    #[cfg(feature = "manual_registry")]
+    let policy = manual_registry::registered_collision_policy();
+    #[cfg(not(feature = "manual_registry"))]
+    #[cfg(not(small_type_id_link_section))]
+    let policy = with_ctors_per_entry::registered_collision_policy();
+    #[cfg(not(feature = "manual_registry"))]
+    #[cfg(small_type_id_link_section)]
+    let policy = with_link_section::registered_collision_policy();
+
+    policy.unwrap_or(CollisionPolicy::Abort)
+}
+
+/// Allows using `core::fmt` machinery for writing to stderr.
+#[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+struct StdErrWriter(platform::StdErr);
+
+#[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+impl core::fmt::Write for StdErrWriter {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        platform::print_error(&mut self.0, s);
+        Ok(())
+    }
+}
+
+/// Reports all registered duplicates and applies selected [`CollisionPolicy`].
+/// Returns only if policy allows to continue execution.
 #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
 #[cold]
 #[inline(never)]
-fn handle_duplicate_typeid(
-    type_id: TypeId,
-    #[cfg(feature = "debug_type_name")] iter_types: impl Iterator<Item = &'static private::TypeEntry>,
-) -> ! {
-    let hex_val = hex::HexView::new(type_id.as_u32());
-
-    #[cfg(feature = "debug_type_name")]
-    let (e0, e1) = {
-        let mut iter_types = iter_types.filter(|x| x.type_id == type_id);
-        let e0 = iter_types.next().unwrap();
-        let e1 = iter_types.next().unwrap();
-        // We order this 2 entries for ease of testing.
-        if e0.type_name <= e1.type_name {
-            (e0, e1)
-        } else {
-            (e1, e0)
-        }
-    };
+fn handle_duplicate_typeids() {
+    use core::fmt::Write as _;
+
+    let report = CollisionReport::new();
+    let policy = current_collision_policy();
+    if let CollisionPolicy::Hook(hook) = policy {
+        hook(&report);
+        return;
+    }
 
-    // Safety: well, we just call libc or WinAPI functions.
+    // Safety: well, we just call libc or WinAPI functions (or make system calls directly).
     // This code runs before main so we cannot run code from stdlib so we can't really synchronize access to stderr.
     // It probably the only running thread in application.
     // Anyway, this function ends by terminates current process so any memory unsafety would end here.
-    unsafe {
-        let mut stderr = platform::get_stderr();
-
-        platform::print_error(&mut stderr, "small_type_id: Found duplicate type_id ");
-        platform::print_error(&mut stderr, hex_val.as_str());
-        #[cfg(not(feature = "debug_type_name"))]
-        {
-            platform::print_error(
-                &mut stderr,
-                r#". Consider enabling "debug_type_name" feature to display conflicting type names"#,
-            );
-        }
-        #[cfg(feature = "debug_type_name")]
-        {
-            platform::print_error(&mut stderr, " for types ");
-            platform::print_error(&mut stderr, e0.type_name);
-            platform::print_error(&mut stderr, " and ");
-            platform::print_error(&mut stderr, e1.type_name);
-        }
-        platform::print_error(&mut stderr, ".\n");
+    let mut stderr = StdErrWriter(unsafe { platform::get_stderr() });
+    // We are trying to output diagnostic info on best effort basis so errors are ignored.
+    let _ = write!(&mut stderr, "{}", report);
+    #[cfg(feature = "json_report")]
+    {
+        // Safety: same as for stderr.
+        let mut output = StdErrWriter(unsafe { platform::get_json_report_output() });
+        let _ = write!(&mut output, "{}", report.json());
+    }
 
-        platform::terminate_current_process(stderr)
+    match policy {
+        CollisionPolicy::Panic => panic!("small_type_id: Found duplicate type_ids"),
+        CollisionPolicy::Warn | CollisionPolicy::Hook(_) => {}
+        CollisionPolicy::Abort => platform::terminate_current_process(stderr.0),
     }
 }
 
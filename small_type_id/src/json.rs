@@ -0,0 +1,168 @@
+//! Machine readable collision report.
+//!
+//! Schema is documented in [feature `json_report`](crate#feature-json_report).
+//! Any change of produced objects must increase [`SCHEMA_VERSION`].
+
+use core::fmt::{Display, Formatter, Result, Write as _};
+
+use crate::{Collision, TypeEntry, TypeId, hex};
+
+pub(crate) const SCHEMA_VERSION: u32 = 1;
+
+/// Environment variable that selects file descriptor (or handle on Windows) for report.
+#[cfg_attr(
+    any(feature = "unsafe_remove_duplicate_checks", target_os = "none"),
+    allow(dead_code)
+)]
+pub(crate) const REPORT_FD_ENV_VAR: &core::ffi::CStr = c"SMALL_TYPE_ID_JSON_REPORT_FD";
+
+/// Parses decimal number of file descriptor from value of [`REPORT_FD_ENV_VAR`].
+#[cfg_attr(
+    any(feature = "unsafe_remove_duplicate_checks", target_os = "none"),
+    allow(dead_code)
+)]
+pub(crate) fn parse_fd(value: &[u8]) -> Option<u32> {
+    if value.is_empty() || value.len() > 10 {
+        return None;
+    }
+    value.iter().try_fold(0u32, |acc, &c| {
+        if c.is_ascii_digit() {
+            acc.checked_mul(10)?.checked_add(u32::from(c - b'0'))
+        } else {
+            None
+        }
+    })
+}
+
+/// Writes every collision as JSON object on its own line.
+///
+/// Holds function that returns duplicate ids.
+pub(crate) struct JsonReport<F>(pub(crate) F);
+
+impl<F, I> Display for JsonReport<F>
+where
+    F: Fn() -> I,
+    I: Iterator<Item = TypeId>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for type_id in (self.0)() {
+            Display::fmt(&JsonCollision(Collision { type_id }), f)?;
+        }
+        Ok(())
+    }
+}
+
+struct JsonCollision(Collision);
+
+impl Display for JsonCollision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let type_id = self.0.type_id();
+        write!(
+            f,
+            r#"{{"schema_version":{},"type_id":{},"type_id_hex":"{}","types":["#,
+            SCHEMA_VERSION,
+            type_id.as_u32(),
+            hex::HexView::new(type_id.as_u32()).as_str(),
+        )?;
+        for (i, entry) in report_types(self.0).enumerate() {
+            if i != 0 {
+                f.write_char(',')?;
+            }
+            write_type(f, entry)?;
+        }
+        f.write_str("]}\n")
+    }
+}
+
+#[cfg(feature = "debug_type_name")]
+fn report_types(collision: Collision) -> impl Iterator<Item = TypeEntry> + use<> {
+    collision.sorted_types()
+}
+
+#[cfg(not(feature = "debug_type_name"))]
+fn report_types(collision: Collision) -> impl Iterator<Item = TypeEntry> + use<> {
+    collision.types()
+}
+
+#[cfg(feature = "debug_type_name")]
+fn write_type(f: &mut Formatter<'_>, entry: TypeEntry) -> Result {
+    f.write_str(r#"{"name":"#)?;
+    write_string(f, entry.debug_type_name)?;
+    f.write_str(r#","crate_version":"#)?;
+    if entry.debug_crate_version.is_empty() {
+        f.write_str("null")?;
+    } else {
+        write_string(f, entry.debug_crate_version)?;
+    }
+    write!(f, r#","seed":{}}}"#, entry.debug_seed)
+}
+
+#[cfg(not(feature = "debug_type_name"))]
+fn write_type(f: &mut Formatter<'_>, _entry: TypeEntry) -> Result {
+    f.write_str(r#"{"name":null,"crate_version":null,"seed":null}"#)
+}
+
+/// Writes quoted JSON string, escaping characters as required by RFC 8259.
+#[cfg_attr(not(feature = "debug_type_name"), allow(dead_code))]
+fn write_string(f: &mut Formatter<'_>, s: &str) -> Result {
+    f.write_char('"')?;
+    let mut rest = s;
+    while let Some(pos) = rest.find(|c: char| c == '"' || c == '\\' || c < ' ') {
+        f.write_str(&rest[..pos])?;
+        let c = rest.as_bytes()[pos];
+        match c {
+            b'"' => f.write_str(r#"\""#)?,
+            b'\\' => f.write_str(r"\\")?,
+            b'\n' => f.write_str(r"\n")?,
+            b'\r' => f.write_str(r"\r")?,
+            b'\t' => f.write_str(r"\t")?,
+            _ => write!(f, r"\u{:04X}", c)?,
+        }
+        rest = &rest[pos + 1..];
+    }
+    f.write_str(rest)?;
+    f.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Quoted(&'static str);
+
+    impl Display for Quoted {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            write_string(f, self.0)
+        }
+    }
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(Quoted("").to_string(), r#""""#);
+        assert_eq!(Quoted("a::B").to_string(), r#""a::B""#);
+        assert_eq!(
+            Quoted("a\"b\\c\nd\re\tf\u{1}g").to_string(),
+            r#""a\"b\\c\nd\re\tf\u0001g""#
+        );
+        assert_eq!(Quoted("тип<'a>").to_string(), r#""тип<'a>""#);
+    }
+
+    #[test]
+    fn parses_fd() {
+        assert_eq!(parse_fd(b"1"), Some(1));
+        assert_eq!(parse_fd(b"2"), Some(2));
+        assert_eq!(parse_fd(b"0042"), Some(42));
+        assert_eq!(parse_fd(b"4294967295"), Some(u32::MAX));
+        assert_eq!(parse_fd(b"4294967296"), None);
+        assert_eq!(parse_fd(b""), None);
+        assert_eq!(parse_fd(b"-1"), None);
+        assert_eq!(parse_fd(b" 1"), None);
+        assert_eq!(parse_fd(b"stdout"), None);
+    }
+
+    #[test]
+    fn empty_report() {
+        let report = JsonReport(core::iter::empty::<TypeId>);
+        assert_eq!(report.to_string(), "");
+    }
+}
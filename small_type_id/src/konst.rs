@@ -0,0 +1,165 @@
+//! Utilities for arrays of [`TypeId`]s usable in const context.
+//!
+//! All functions use simple algorithms with quadratic complexity
+//! because they are intended for small arrays of types known at compile time.
+//!
+//! ```
+//! use small_type_id::{TypeId, konst};
+//!
+//! #[derive(small_type_id::HasTypeId)]
+//! struct A;
+//! #[derive(small_type_id::HasTypeId)]
+//! struct B;
+//!
+//! const TYPES: [TypeId; 2] = konst::sort([TypeId::of::<B>(), TypeId::of::<A>()]);
+//! const { assert!(konst::is_unique(&TYPES)) };
+//! const { assert!(konst::contains(&TYPES, TypeId::of::<A>())) };
+//! assert!(TYPES[0] < TYPES[1]);
+//! ```
+
+use crate::TypeId;
+
+/// Returns `true` if there are no equal [`TypeId`]s in `ids`.
+#[must_use]
+pub const fn is_unique(ids: &[TypeId]) -> bool {
+    let mut i = 0;
+    // Cannot use for loops because it is a const function.
+    while i < ids.len() {
+        let mut j = i + 1;
+        while j < ids.len() {
+            if ids[i].eq(&ids[j]) {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns index of first occurrence of `id` in `ids`.
+#[must_use]
+pub const fn index_of(ids: &[TypeId], id: TypeId) -> Option<usize> {
+    let mut i = 0;
+    while i < ids.len() {
+        if ids[i].eq(&id) {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns `true` if `ids` contain `id`.
+#[must_use]
+pub const fn contains(ids: &[TypeId], id: TypeId) -> bool {
+    index_of(ids, id).is_some()
+}
+
+/// Returns `true` if slices have same [`TypeId`]s in same order.
+/// Same as `==` but can be used in const context.
+#[must_use]
+pub const fn eq(a: &[TypeId], b: &[TypeId]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if !a[i].eq(&b[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns array sorted in ascending order.
+/// Produces same order as [`slice::sort`].
+#[must_use]
+pub const fn sort<const N: usize>(mut ids: [TypeId; N]) -> [TypeId; N] {
+    // Insertion sort.
+    let mut i = 1;
+    while i < N {
+        let mut j = i;
+        while j > 0 && ids[j - 1].as_u32() > ids[j].as_u32() {
+            ids.swap(j - 1, j);
+            j -= 1;
+        }
+        i += 1;
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn ids<const N: usize>(vals: [u32; N]) -> [TypeId; N] {
+        let mut res = [TypeId::of::<Dummy>(); N];
+        let mut i = 0;
+        while i < N {
+            res[i] = match TypeId::from_u32(vals[i]) {
+                Ok(x) => x,
+                Err(_) => panic!("Invalid TypeId"),
+            };
+            i += 1;
+        }
+        res
+    }
+
+    struct Dummy;
+
+    // SAFETY: Only used as placeholder in tests.
+    unsafe impl crate::HasTypeId for Dummy {
+        const TYPE_ID: TypeId = match TypeId::from_u32(1) {
+            Ok(x) => x,
+            Err(_) => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn unique() {
+        assert!(is_unique(&[]));
+        assert!(is_unique(&ids([5])));
+        assert!(is_unique(&ids([5, 3, 8, 1])));
+        assert!(!is_unique(&ids([5, 3, 5])));
+        assert!(!is_unique(&ids([1, 2, 3, 3])));
+        const { assert!(is_unique(&ids([1, 2]))) };
+    }
+
+    #[test]
+    fn lookup() {
+        let arr = ids([5, 3, 8, 3]);
+        assert_eq!(index_of(&arr, arr[0]), Some(0));
+        assert_eq!(index_of(&arr, arr[1]), Some(1));
+        assert_eq!(index_of(&arr, arr[2]), Some(2));
+        assert_eq!(index_of(&arr, ids([7])[0]), None);
+        assert_eq!(index_of(&[], arr[0]), None);
+        assert!(contains(&arr, arr[2]));
+        assert!(!contains(&arr, ids([7])[0]));
+    }
+
+    #[test]
+    fn equality() {
+        assert!(eq(&[], &[]));
+        assert!(eq(&ids([5, 3]), &ids([5, 3])));
+        assert!(!eq(&ids([5, 3]), &ids([3, 5])));
+        assert!(!eq(&ids([5, 3]), &ids([5])));
+        const { assert!(eq(&sort(ids([5, 3])), &sort(ids([3, 5])))) };
+    }
+
+    #[test]
+    fn sorts() {
+        assert_eq!(sort::<0>([]), []);
+        assert_eq!(sort(ids([5])), ids([5]));
+        assert_eq!(sort(ids([5, 3, 8, 3, 1])), ids([1, 3, 3, 5, 8]));
+        assert_eq!(sort(ids([1, 2, 3])), ids([1, 2, 3]));
+        assert_eq!(sort(ids([3, 2, 1])), ids([1, 2, 3]));
+        let vals = [0x7FFF_FFFF, 17, 0x1234_5678, 2, 0x7FFF_FFFE, 17];
+        let mut expected = ids(vals);
+        expected.sort();
+        assert_eq!(sort(ids(vals)), expected);
+        const SORTED: [TypeId; 3] = sort(ids([9, 4, 6]));
+        assert_eq!(SORTED, ids([4, 6, 9]));
+    }
+}
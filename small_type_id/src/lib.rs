@@ -60,10 +60,14 @@
 //!
 //! All invocations of [`HasTypeId`](derive.HasTypeId.html) macro generate
 //! code that registers the type in big list of types that have [`TypeId`].
-//! Then, verification code is executed before `main`.
+//! Then, verification code is executed before `main`
+//! (or on first use if [`lazy_verification`](#feature-lazy_verification) is enabled).
 //!
-//! Verification code generally executes with complexity _O(n<sup>2</sup>)_
-//! although with very small constant on Windows and Linux, e.g. it processes 60000 types faster than 100 ms in debug build.
+//! On Windows and Linux, and with [`linker_script_registration`](#feature-linker_script_registration),
+//! verification sorts copies of registered ids in place
+//! so it executes with complexity _O(n*log(n))_ and doesn't allocate.
+//! On other platforms every type is registered by its own constructor which inserts it into skip list
+//! so verification executes with complexity _O(n*log(n))_ too.
 //!
 //! However, if it is inacceptible, it can be disabled using [`unsafe_remove_duplicate_checks`](#feature-unsafe_remove_duplicate_checks)
 //! feature. Enabling this feature is equivalent to **running unsafe code** so please consult it documentation
@@ -71,12 +75,13 @@
 //!
 //! If duplicate `TypeId`s detected, program would write some debug information to stderr
 //! and terminate with error before reaching `main`.
+//! Final binary crate can select different behaviour using [`collision_policy!`] macro.
 //!
 //! ## Available features
 //!
 //! ### Feature `debug_type_name`
 //!
-//! Saves type name in derive invocation of [`HasTypeId`](derive.HasTypeId.html) macro,
+//! Saves type name, crate version and seed in derive invocation of [`HasTypeId`](derive.HasTypeId.html) macro,
 //! allowing to printing conflicting types in case of collision of [`HasTypeId::TYPE_ID`] values.
 //!
 //! The purpose of this feature only to debug cases of [`TypeId`] collisions.
@@ -86,16 +91,162 @@
 //! ### Feature `unsafe_remove_duplicate_checks`
 //!
 //! Disables automatic verification of uniqueness of [`TypeId`]s.
-//! Use [`iter_registered_types`] function to run verification yourself.
+//! Use [`verify_unique`] function or [`test_unique_type_ids!`] macro to run verification yourself.
 //!
 //! The purpose of this feature is
 //!
 //! * to avoid running any code before `main`
-//! * to avoid _O(n<sup>2</sup>)_ complexity of automatic verification
-//! * to prevent linking with libc or kernel32.
+//! * to avoid any cost of automatic verification
+//! * to prevent linking with libc or kernel32 (on Linux, [`linux_raw_syscalls`](#feature-linux_raw_syscalls) can be used instead).
 //!
 //! Please, don't enable this feauture in library crates. This should be done only
 //! in final binary crates because it may affect other libraries.
+//! If you only need to handle collisions differently, prefer selecting
+//! [`CollisionPolicy`] using [`collision_policy!`] in binary crate.
+//!
+//! ### Feature `force_ctor_registration`
+//!
+//! Registers types using constructors on Windows and Linux too,
+//! like it is done on other platforms, instead of putting them into link sections.
+//!
+//! The purpose of this feature is to test constructor based registration
+//! and to support linkers that discard or reorder custom link sections.
+//!
+//! ### Feature `lazy_verification`
+//!
+//! Defers verification of uniqueness of [`TypeId`]s from start of the program
+//! until first call of [`ensure_verified`], [`iter_registered_types`] or [`find_registered_type`].
+//! Verification runs exactly once, even if those functions are called from multiple threads.
+//!
+//! The purpose of this feature is to avoid running verification in short-lived programs
+//! and to avoid running constructors on Windows and Linux.
+//! On other platforms, or with [`force_ctor_registration`](#feature-force_ctor_registration),
+//! types are still registered using constructors but found duplicates are reported only during verification.
+//!
+//! Note that values of [`HasTypeId::TYPE_ID`] are not verified until verification runs
+//! so code that relies on their uniqueness should call [`ensure_verified`] first, e.g. at start of `main`.
+//! Downcasts of [`SmallAny`](any::SmallAny) and `ThinAny`, insertions into `TypeMap`, `HeaplessTypeMap`
+//! and `CommandBuffer` and registration of its handlers do this automatically.
+//!
+//! ### Feature `manual_registry`
+//!
+//! Disables automatic registration of types by [`HasTypeId`](derive.HasTypeId.html) macro.
+//! Instead, final binary lists all types in [`registry!`] macro
+//! which defines static table used by [`iter_registered_types`], [`find_registered_type`] and verification.
+//! Verification runs on first use, like with [`lazy_verification`](#feature-lazy_verification).
+//!
+//! The purpose of this feature is to support environments that
+//! support neither link sections nor constructors, e.g. Miri.
+//!
+//! Every binary, including tests, must invoke [`registry!`] exactly once if it uses registered types,
+//! otherwise it would fail to link.
+//! Since features are unified, this applies to every binary in dependency graph,
+//! so libraries must not enable this feature.
+//! Enable it only for test targets that invoke [`registry!`], e.g.
+//! `cargo test --features=small_type_id/manual_registry --test uses_registry`.
+//! Doctests and other test targets should be run without it.
+//!
+//! ### Feature `json_report`
+//!
+//! Writes machine readable report about found collisions in addition to human readable one.
+//! Report is written to stderr or to file descriptor (handle on Windows) which number is put
+//! into `SMALL_TYPE_ID_JSON_REPORT_FD` environment variable, e.g. `1` for stdout.
+//! Same report is returned by `json` methods of [`CollisionReport`] and [`DuplicateTypeIds`].
+//!
+//! Report contains one JSON object per line for every duplicate [`TypeId`]:
+//!
+//! ```json
+//! {"schema_version":1,"type_id":1345629155,"type_id_hex":"5034ABE3","types":[{"name":"my_crate::A","crate_version":"0.1.0","seed":0},{"name":"my_crate::B","crate_version":"0.1.0","seed":0}]}
+//! ```
+//!
+//! * `schema_version` is increased on every incompatible change of report format.
+//!   Addition of new fields is not considered incompatible.
+//! * `type_id` is value of [`TypeId::as_u32`], and `type_id_hex` is its uppercase hexadecimal representation.
+//! * `types` lists every registered type with this id, ordered by names if they are available.
+//! * `name`, `crate_version` and `seed` are `null` if feature [`debug_type_name`](#feature-debug_type_name) is disabled.
+//!   `crate_version` is also `null` if type is declared outside of cargo package.
+//!
+//! Unlike text of human readable report, this format is covered by [semver policy](#semver-breaking-policy).
+//!
+//! ### Feature `linux_raw_syscalls`
+//!
+//! On Linux, reports collisions and terminates process using system calls directly
+//! instead of calling libc functions. Supported only on `x86_64`, `aarch64` and `riscv64` architectures.
+//!
+//! The purpose of this feature is to keep verification in binaries that don't link libc at all,
+//! e.g. static binaries that use `-nostartfiles`.
+//! Enabling it on other operating systems does nothing.
+//!
+//! ### Feature `linker_script_registration`
+//!
+//! Puts registered types into link sections whose boundaries are provided by linker script,
+//! instead of relying on platform specific linker behaviour. Works on any target with ELF-like linker,
+//! including bare metal targets without operating system, which are supported only with this feature
+//! or with [`manual_registry`](#feature-manual_registry).
+//!
+//! Entries of types are put into sections with names starting from `.small_type_id_registry.entries.`,
+//! which must be kept and sorted by name. Headers that allow finding entries of every crate version
+//! are put into `.small_type_id_registry.headers` section, which must be surrounded
+//! by `__small_type_id_registries_start` and `__small_type_id_registries_end` symbols.
+//! Collision policy is put into `.small_type_id_registry.policies` section, which must be surrounded
+//! by `__small_type_id_policies_start` and `__small_type_id_policies_end` symbols.
+//! Copies of ids, which are sorted in place during verification, are put into sections
+//! with names starting from `.small_type_id_ids.`, which must be kept, sorted by name
+//! and placed into writable memory.
+//!
+//! Crate ships [`small_type_id.ld`][5] file which does exactly this.
+//! On hosted targets it can be passed to linker directly, e.g. using
+//! `RUSTFLAGS="-C link-arg=-T/path/to/small_type_id.ld"`, because it only inserts section after `.data`.
+//! On bare metal targets, copy its output sections into linker script of firmware.
+//! If script is not used, program fails to link because of missing symbols.
+//!
+//! On targets with operating system, verification runs before `main` using constructor,
+//! unless [`lazy_verification`](#feature-lazy_verification) is enabled.
+//! On targets without operating system, it runs on first use, like with
+//! [`lazy_verification`](#feature-lazy_verification), because no code is executed before `main`.
+//! If [`manual_registry`](#feature-manual_registry) or
+//! [`force_ctor_registration`](#feature-force_ctor_registration) is enabled, this feature does nothing.
+//! On targets without operating system, collisions are not printed and verification panics.
+//!
+//! ### Feature `serde`
+//!
+//! Implements `Serialize` and `Deserialize` traits of [`serde`][6] for [`TypeId`], and `Serialize` for [`TypeEntry`].
+//!
+//! Human readable formats, like JSON, represent [`TypeId`] as hexadecimal string produced by
+//! its [`Display`](core::fmt::Display) implementation, while binary formats use [`u32`].
+//! Deserialization rejects values that violate invariants of [`TypeId`] but accepts unknown ids.
+//! Use `#[serde(with = "small_type_id::serde::registered")]` to accept only ids of registered types.
+//!
+//! ### Features `bytemuck` and `zerocopy`
+//!
+//! Implement traits of [`bytemuck`][7] and [`zerocopy`][8] crates which allow
+//! reinterpreting memory of [`TypeId`] and `Option<TypeId>`, e.g. in memory mapped tables.
+//!
+//! With `bytemuck`, [`TypeId`] implements `NoUninit`, `ZeroableInOption` and `CheckedBitPattern`.
+//! The latter accepts only values that satisfy invariants of [`TypeId`], same as [`TypeId::from_u32`],
+//! so functions from `bytemuck::checked` can safely cast bytes to [`TypeId`]s.
+//! `Option<TypeId>` implements `Zeroable`.
+//!
+//! With `zerocopy`, [`TypeId`] implements `IntoBytes`, `KnownLayout` and `Immutable`.
+//! `TryFromBytes` is not implemented because `zerocopy` allows only derived implementations,
+//! which cannot check that most significant bit is zero or that id is registered,
+//! and methods that perform the check are hidden and change between releases of `zerocopy` 0.8.
+//! Read values as [`u32`] and convert them using [`TypeId::from_u32`]
+//! or [`TypeId::from_registered_bytes`] instead.
+//!
+//! ### Feature `alloc`
+//!
+//! Enables items that require allocator, e.g. [`TypeIdMap`](hash::TypeIdMap) and
+//! [`TypeIdSet`](hash::TypeIdSet) aliases for collections of [`hashbrown`][9] crate
+//! that use [`BuildTypeIdHasher`](hash::BuildTypeIdHasher),
+//! [`TypeMap`](type_map::TypeMap) container and downcasting of `Box`, `Rc` and `Arc`
+//! of [`SmallAny`](any::SmallAny) trait objects using [`Downcast`](any::Downcast),
+//! [`ThinAny`](any::ThinAny) and [`ThinAnySend`](any::ThinAnySend) pointers and [`CommandBuffer`](command_buffer::CommandBuffer).
+//!
+//! ### Feature `heapless`
+//!
+//! Enables [`HeaplessTypeMap`](type_map::HeaplessTypeMap) that is implemented using [`heapless`][10] crate
+//! and stores values inline so it can be used without allocator.
 //!
 //! ### Feature `unsafe_dont_register_types`
 //!
@@ -113,7 +264,7 @@
 //!
 //! The following changes are not considered breaking:
 //!
-//! 1. Changes of string representation of type ids
+//! 1. Changes of string representation of type ids and of human readable collision reports
 //! 2. Changes of uniqueness verification algorithm
 //! 3. Change of type id generation algorithm (and resulting values of type ids)
 //! 4. Changes of type registration code
@@ -139,28 +290,14 @@
 //! Detect that types are same or not in generic code in compile time.
 //!
 //! ```
-//! # use small_type_id::HasTypeId;
+//! # use small_type_id::{HasTypeId, TypeId};
 //! const fn is_types_unique<T0, T1, T2>()->bool
 //! where
 //!     T0: HasTypeId,
 //!     T1: HasTypeId,
 //!     T2: HasTypeId,
 //! {
-//!     let types = [T0::TYPE_ID, T1::TYPE_ID, T2::TYPE_ID];
-//!     let mut i = 0;
-//!     // Cannot use for loops because it is a const function.
-//!     while i < types.len() {
-//!         let t = types[i];
-//!         let mut j = i + 1;
-//!         while j < types.len() {
-//!             if t.as_u32() == types[j].as_u32() {
-//!                 return false;
-//!             }
-//!             j += 1;
-//!         }
-//!         i += 1;
-//!     }
-//!     true
+//!     small_type_id::konst::is_unique(&[TypeId::of::<T0>(), TypeId::of::<T1>(), TypeId::of::<T2>()])
 //! }
 //!
 //! #[derive(small_type_id::HasTypeId)]
@@ -198,27 +335,83 @@
 //! We collect all derived types either by using statics linked to special section (on Linux and Windows),
 //! or put them in a linked list by running code before `main`. We run code before `main` using crate [`ctor`][4].
 //!
+//! Every version of this crate uses its own link section for entries, so semver incompatible versions
+//! can be linked into same binary. Every version also puts a small header into a link section
+//! shared by all versions, which allows finding entries of all versions linked into binary
+//! and verifying their uniqueness together. Versions released before introduction of such header
+//! are not visible to newer versions.
+//!
 //!
 //!
 //! [1]: https://doc.rust-lang.org/std/option/index.html#representation
 //! [2]: https://rust-unofficial.github.io/patterns/patterns/behavioural/newtype.html
 //! [3]: https://xxhash.com/
 //! [4]: https://crates.io/crates/ctor
+//! [5]: https://github.com/AngelicosPhosphoros/small_type_id/blob/main/small_type_id/small_type_id.ld
+//! [6]: https://crates.io/crates/serde
+//! [7]: https://crates.io/crates/bytemuck
+//! [8]: https://crates.io/crates/zerocopy
+//! [9]: https://crates.io/crates/hashbrown
+//! [10]: https://crates.io/crates/heapless
 //!
 
 #![deny(unsafe_op_in_unsafe_fn)]
 #![allow(clippy::uninlined_format_args, clippy::collapsible_if)]
 #![cfg_attr(not(test), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use core::num::NonZeroU32;
 
+pub mod any;
+#[cfg(feature = "bytemuck")]
+mod bytemuck_impls;
+#[cfg(feature = "alloc")]
+pub mod command_buffer;
+pub mod dispatch;
+// Used algorithm depends on registration backend.
+#[allow(dead_code)]
+mod duplicates;
+pub mod hash;
 mod hex;
 mod implementation;
+#[cfg(feature = "json_report")]
+mod json;
+pub mod konst;
 mod macros;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod slice;
+#[cfg(feature = "alloc")]
+mod thin_any;
+pub mod type_list;
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+pub mod type_map;
 
-#[cfg(any(test, doctest, not(any(target_os = "windows", target_os = "linux"))))]
+#[cfg(any(
+    test,
+    doctest,
+    all(
+        not(feature = "unsafe_remove_duplicate_checks"),
+        not(feature = "manual_registry"),
+        not(small_type_id_link_section)
+    )
+))]
 mod skip_list;
 
+#[cfg(all(
+    target_os = "none",
+    not(any(
+        all(small_type_id_link_section, feature = "linker_script_registration"),
+        feature = "manual_registry",
+        feature = "unsafe_dont_register_types"
+    ))
+))]
+compile_error!(
+    r#"Targets without operating system require feature "linker_script_registration" or "manual_registry"."#
+);
+
 pub use implementation::private;
 
 /// Implements [`HasTypeId`] trait and registers implementation for runtime verification.
@@ -266,6 +459,10 @@ pub use small_type_id_proc_macro::HasTypeId;
 /// Unique id for a type.
 /// Have extra invariants about internal structure, described in [module documentation](index.html).
 #[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(zerocopy::IntoBytes, zerocopy::KnownLayout, zerocopy::Immutable)
+)]
 #[repr(transparent)]
 #[non_exhaustive]
 pub struct TypeId(pub(crate) NonZeroU32);
@@ -295,6 +492,10 @@ pub struct TypeId(pub(crate) NonZeroU32);
 pub unsafe trait HasTypeId: 'static {
     /// Unique identifier of type.
     const TYPE_ID: TypeId;
+
+    // Used by derive and `registry!` macros to save debug information.
+    #[doc(hidden)]
+    const __DEBUG_INFO: private::DebugInfo = private::DebugInfo::UNKNOWN;
 }
 
 /// Entry that describes registered type information.
@@ -309,15 +510,148 @@ pub struct TypeEntry {
     /// Available only if feature [`debug_type_name`](./index.html#feature-debug_type_name) is enabled.
     #[cfg(feature = "debug_type_name")]
     pub debug_type_name: &'static str,
+    /// Version of crate that declares the type or empty string if unknown.
+    /// Available only if feature [`debug_type_name`](./index.html#feature-debug_type_name) is enabled.
+    #[cfg(feature = "debug_type_name")]
+    pub debug_crate_version: &'static str,
+    /// Seed selected by `#[small_type_id_seed]` attribute, 0 by default.
+    /// Available only if feature [`debug_type_name`](./index.html#feature-debug_type_name) is enabled.
+    #[cfg(feature = "debug_type_name")]
+    pub debug_seed: u32,
 }
 
 /// Allows iteration over types that implemented [`HasTypeId`] trait using derive macro.
 ///
+/// On Linux and Windows, it also yields types registered by other versions of this crate linked into binary.
+/// Their `debug_type_name` is a placeholder if that version doesn't save type names.
+///
 /// Doesn't work if feature [`unsafe_dont_register_types`](./index.html#feature-unsafe_dont_register_types) is enabled.
+///
+/// Calls [`ensure_verified`] before iteration.
 pub fn iter_registered_types() -> impl Iterator<Item = TypeEntry> {
+    ensure_verified();
     implementation::pub_iter_registered_types()
 }
 
+/// Finds registered type with given [`TypeId`].
+///
+/// Runs in _O(n)_ time so it is mostly useful for debugging and diagnostics.
+///
+/// Calls [`ensure_verified`] before lookup.
+///
+/// ```
+/// use small_type_id::HasTypeId as _;
+///
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Lookup;
+///
+/// # #[cfg(not(any(miri, feature = "unsafe_dont_register_types")))]
+/// assert!(small_type_id::find_registered_type(Lookup::TYPE_ID).is_some());
+/// ```
+#[must_use]
+pub fn find_registered_type(type_id: TypeId) -> Option<TypeEntry> {
+    iter_registered_types().find(|e| e.type_id == type_id)
+}
+
+/// Checks that all registered [`TypeId`]s are unique.
+///
+/// Uses the same algorithm as automatic verification but returns found duplicates
+/// instead of applying [`CollisionPolicy`].
+/// This is useful if automatic verification is disabled using
+/// [`unsafe_remove_duplicate_checks`](./index.html#feature-unsafe_remove_duplicate_checks) feature.
+/// Use [`test_unique_type_ids!`] to generate test that calls this function.
+///
+/// # Errors
+///
+/// Returns error if some registered types have same [`TypeId`].
+///
+/// ```
+/// # #[cfg(not(miri))]
+/// small_type_id::verify_unique().unwrap();
+/// ```
+pub fn verify_unique() -> Result<(), DuplicateTypeIds> {
+    if implementation::has_duplicate_type_ids() {
+        Err(DuplicateTypeIds::new())
+    } else {
+        Ok(())
+    }
+}
+
+/// Verifies uniqueness of registered [`TypeId`]s if it isn't done yet.
+///
+/// If feature [`lazy_verification`](./index.html#feature-lazy_verification) is enabled,
+/// verification runs exactly once, on first call of this function,
+/// [`iter_registered_types`] or [`find_registered_type`].
+/// Concurrent calls wait until verification is finished.
+/// Found duplicates are handled according to selected [`CollisionPolicy`]
+/// so this function returns only if there are no duplicates or policy allows to continue.
+///
+/// Otherwise, verification already happened before `main` and this function does nothing.
+pub fn ensure_verified() {
+    implementation::ensure_verified();
+}
+
+/// Action taken when verification finds duplicate [`TypeId`]s.
+///
+/// Selected by the final binary using [`collision_policy!`] macro.
+/// If binary doesn't select any, [`CollisionPolicy::Abort`] is used.
+#[non_exhaustive]
+#[derive(Clone, Copy)]
+pub enum CollisionPolicy {
+    /// Write report to stderr and abort the process.
+    Abort,
+    /// Write report to stderr and panic.
+    ///
+    /// Note that panics in code that runs before `main` abort the process anyway.
+    Panic,
+    /// Write report to stderr and continue execution.
+    ///
+    /// Execution continues with non-unique [`TypeId`]s
+    /// so it can be selected only using `unsafe` block in [`collision_policy!`].
+    Warn,
+    /// Call the hook and continue execution if it returns.
+    ///
+    /// Hook may be called before `main` so it must not rely on anything initialized in `main`.
+    /// If verification is lazy, hook must not call [`ensure_verified`] or functions that call it,
+    /// including downcasts and insertions into `TypeMap`,
+    /// because it would wait for end of verification forever.
+    /// Since execution continues with non-unique [`TypeId`]s if hook returns,
+    /// it can be selected only using `unsafe` block in [`collision_policy!`].
+    Hook(fn(&CollisionReport)),
+}
+
+/// Describes duplicate [`TypeId`]s found during verification.
+///
+/// Passed to the hook selected by [`CollisionPolicy::Hook`].
+/// Its [`Display`](core::fmt::Display) implementation produces the same text that
+/// is written to stderr by other policies.
+pub struct CollisionReport {
+    // Prevent construction in user code.
+    _x: (),
+}
+
+/// Error returned by [`verify_unique`].
+///
+/// Its [`Display`](core::fmt::Display) implementation produces the same text as [`CollisionReport`].
+///
+/// If feature `alloc` is enabled, found duplicates are saved into error
+/// and available using [`DuplicateTypeIds::groups`].
+/// Otherwise, registered types are scanned again every time error is inspected.
+pub struct DuplicateTypeIds {
+    /// Every duplicate [`TypeId`] with all types that share it.
+    #[cfg(feature = "alloc")]
+    groups: alloc::vec::Vec<(TypeId, alloc::vec::Vec<TypeEntry>)>,
+    // Prevent construction in user code.
+    #[cfg(not(feature = "alloc"))]
+    _x: (),
+}
+
+/// Single [`TypeId`] shared by multiple registered types.
+#[derive(Clone, Copy)]
+pub struct Collision {
+    type_id: TypeId,
+}
+
 /// Error type for [`TypeId::from_bytes`].
 #[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
 pub struct ErrorInvalidBytes {
@@ -325,10 +659,93 @@ pub struct ErrorInvalidBytes {
     _x: (),
 }
 
+/// Error type for safe constructors of [`TypeId`], e.g. [`TypeId::from_u32`].
+///
+/// Describes why value cannot be a valid [`TypeId`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub enum ErrorInvalidTypeId {
+    /// Value is zero.
+    Zero,
+    /// Most significant bit of value is set.
+    MostSignificantBitSet,
+    /// Value conforms to invariants but doesn't belong to any registered type.
+    /// Returned only by [`TypeId::from_registered_bytes`].
+    Unregistered,
+}
+
+/// Error type for [`FromStr`](core::str::FromStr) implementation of [`TypeId`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub enum ErrorParseTypeId {
+    /// String is not a hexadecimal number that fits into [`u32`].
+    InvalidFormat,
+    /// Parsed number is not a valid [`TypeId`].
+    InvalidValue(ErrorInvalidTypeId),
+}
+
 impl TypeId {
+    /// Returns [`TypeId`] of type `T`.
+    /// Same as `T::TYPE_ID` but doesn't require importing [`HasTypeId`].
+    ///
+    /// ```
+    /// use small_type_id::TypeId;
+    ///
+    /// #[derive(small_type_id::HasTypeId)]
+    /// struct Type1;
+    /// #[derive(small_type_id::HasTypeId)]
+    /// struct Type2;
+    ///
+    /// const { assert!(!TypeId::of::<Type1>().eq(&TypeId::of::<Type2>())) };
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn of<T: HasTypeId + ?Sized>() -> Self {
+        T::TYPE_ID
+    }
+
+    /// Returns [`TypeId`] of type of referenced value.
+    ///
+    /// ```
+    /// use small_type_id::TypeId;
+    ///
+    /// #[derive(small_type_id::HasTypeId)]
+    /// struct Type1;
+    ///
+    /// assert_eq!(TypeId::of_val(&Type1), TypeId::of::<Type1>());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn of_val<T: HasTypeId + ?Sized>(_val: &T) -> Self {
+        T::TYPE_ID
+    }
+
+    /// Same as [`PartialEq::eq`] but usable in const context.
+    #[must_use]
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub const fn eq(&self, other: &Self) -> bool {
+        self.as_u32() == other.as_u32()
+    }
+
+    /// Same as [`Ord::cmp`] but usable in const context.
+    #[must_use]
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub const fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        let (a, b) = (self.as_u32(), other.as_u32());
+        if a < b {
+            core::cmp::Ordering::Less
+        } else if a == b {
+            core::cmp::Ordering::Equal
+        } else {
+            core::cmp::Ordering::Greater
+        }
+    }
+
     /// Returns value of type id as [`u32`].
-    /// Useful for comparing type ids in const context
-    /// because [`PartialEq`] trait doesn't work in it.
+    /// Useful for computing keys in const context,
+    /// use [`TypeId::eq`] and [`TypeId::cmp`] for comparisons.
     ///
     /// ```
     /// # use small_type_id::HasTypeId as _;
@@ -365,8 +782,25 @@ impl TypeId {
         self.0.get().to_le_bytes()
     }
 
+    /// Same as [`TypeId::to_bytes`] but uses big-endian byte order.
+    #[must_use]
+    #[inline]
+    pub const fn to_be_bytes(self) -> [u8; 4] {
+        self.0.get().to_be_bytes()
+    }
+
+    /// Same as [`TypeId::to_bytes`] but uses native byte order.
+    #[must_use]
+    #[inline]
+    pub const fn to_ne_bytes(self) -> [u8; 4] {
+        self.0.get().to_ne_bytes()
+    }
+
     /// Allows deserializing value from bytes.
     ///
+    /// Prefer [`TypeId::from_le_bytes`] or [`TypeId::from_registered_bytes`]
+    /// which don't require `unsafe`.
+    ///
     /// # Safety
     ///
     /// Bytes should be from call to [`TypeId::to_bytes`].
@@ -377,15 +811,124 @@ impl TypeId {
     /// (doesn't conform to invariants).
     #[inline]
     pub const unsafe fn from_bytes(bytes: [u8; 4]) -> Result<Self, ErrorInvalidBytes> {
+        match Self::from_le_bytes(bytes) {
+            Ok(x) => Ok(x),
+            Err(_) => Err(ErrorInvalidBytes { _x: () }),
+        }
+    }
+
+    /// Checks that value conforms to invariants of `TypeId`.
+    ///
+    /// Note that it doesn't check that any type has this `TypeId`,
+    /// use [`TypeId::from_registered_bytes`] for that.
+    ///
+    /// ```
+    /// use small_type_id::{ErrorInvalidTypeId, TypeId};
+    ///
+    /// assert_eq!(TypeId::from_u32(0x1234_5678).map(TypeId::as_u32), Ok(0x1234_5678));
+    /// assert_eq!(TypeId::from_u32(0), Err(ErrorInvalidTypeId::Zero));
+    /// assert_eq!(TypeId::from_u32(0x8000_0001), Err(ErrorInvalidTypeId::MostSignificantBitSet));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorInvalidTypeId::Zero`] or [`ErrorInvalidTypeId::MostSignificantBitSet`]
+    /// if value violates invariants.
+    #[inline]
+    pub const fn from_u32(val: u32) -> Result<Self, ErrorInvalidTypeId> {
         const ALLOWED_BITS: u32 = u32::MAX >> 1;
-        let val = u32::from_le_bytes(bytes);
         match NonZeroU32::new(val) {
-            Some(x) if val & ALLOWED_BITS == val => Ok(Self(x)),
-            _ => Err(ErrorInvalidBytes { _x: () }),
+            None => Err(ErrorInvalidTypeId::Zero),
+            Some(_) if val & ALLOWED_BITS != val => Err(ErrorInvalidTypeId::MostSignificantBitSet),
+            Some(x) => Ok(Self(x)),
+        }
+    }
+
+    /// Reads value written by [`TypeId::to_bytes`].
+    /// Same as `TryFrom<[u8; 4]>`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TypeId::from_u32`].
+    #[inline]
+    pub const fn from_le_bytes(bytes: [u8; 4]) -> Result<Self, ErrorInvalidTypeId> {
+        Self::from_u32(u32::from_le_bytes(bytes))
+    }
+
+    /// Reads value written by [`TypeId::to_be_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TypeId::from_u32`].
+    #[inline]
+    pub const fn from_be_bytes(bytes: [u8; 4]) -> Result<Self, ErrorInvalidTypeId> {
+        Self::from_u32(u32::from_be_bytes(bytes))
+    }
+
+    /// Reads value written by [`TypeId::to_ne_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TypeId::from_u32`].
+    #[inline]
+    pub const fn from_ne_bytes(bytes: [u8; 4]) -> Result<Self, ErrorInvalidTypeId> {
+        Self::from_u32(u32::from_ne_bytes(bytes))
+    }
+
+    /// Reads value written by [`TypeId::to_bytes`] and checks that it belongs to registered type.
+    /// Useful for decoding untrusted input.
+    ///
+    /// Uses [`find_registered_type`] so it has same complexity and also calls [`ensure_verified`].
+    /// Always fails if feature [`unsafe_dont_register_types`](./index.html#feature-unsafe_dont_register_types) is enabled.
+    ///
+    /// ```
+    /// use small_type_id::{ErrorInvalidTypeId, HasTypeId as _, TypeId};
+    ///
+    /// #[derive(small_type_id::HasTypeId)]
+    /// struct Message;
+    ///
+    /// # #[cfg(not(any(miri, feature = "unsafe_dont_register_types")))]
+    /// # {
+    /// let bytes = Message::TYPE_ID.to_bytes();
+    /// assert_eq!(TypeId::from_registered_bytes(bytes), Ok(Message::TYPE_ID));
+    /// # }
+    /// assert_eq!(TypeId::from_registered_bytes([0; 4]), Err(ErrorInvalidTypeId::Zero));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TypeId::from_le_bytes`], or [`ErrorInvalidTypeId::Unregistered`]
+    /// if there is no registered type with this `TypeId`.
+    pub fn from_registered_bytes(bytes: [u8; 4]) -> Result<Self, ErrorInvalidTypeId> {
+        let type_id = Self::from_le_bytes(bytes)?;
+        match find_registered_type(type_id) {
+            Some(_) => Ok(type_id),
+            None => Err(ErrorInvalidTypeId::Unregistered),
         }
     }
 }
 
+impl TryFrom<u32> for TypeId {
+    type Error = ErrorInvalidTypeId;
+
+    /// Same as [`TypeId::from_u32`].
+    #[inline]
+    fn try_from(val: u32) -> Result<Self, Self::Error> {
+        Self::from_u32(val)
+    }
+}
+
+impl TryFrom<[u8; 4]> for TypeId {
+    type Error = ErrorInvalidTypeId;
+
+    /// Same as [`TypeId::from_le_bytes`].
+    #[inline]
+    fn try_from(bytes: [u8; 4]) -> Result<Self, Self::Error> {
+        Self::from_le_bytes(bytes)
+    }
+}
+
+/// Same as [`UpperHex`](core::fmt::UpperHex).
 impl core::fmt::Debug for TypeId {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -393,6 +936,17 @@ impl core::fmt::Debug for TypeId {
     }
 }
 
+/// Same as [`UpperHex`](core::fmt::UpperHex).
+/// Output can be parsed back using [`FromStr`](core::str::FromStr).
+///
+/// ```
+/// # use small_type_id::HasTypeId as _;
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Logged;
+///
+/// let text = format!("{:08}", Logged::TYPE_ID);
+/// assert_eq!(text.parse(), Ok(Logged::TYPE_ID));
+/// ```
 impl core::fmt::Display for TypeId {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -400,14 +954,234 @@ impl core::fmt::Display for TypeId {
     }
 }
 
+/// Respects width, fill, alignment, `0` and `#` flags like implementation for [`u32`].
 impl core::fmt::UpperHex for TypeId {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let hx = hex::HexView::new(self.as_u32());
-        f.write_str(hx.as_str())
+        f.pad_integral(true, "0x", hx.as_str())
+    }
+}
+
+/// Respects width, fill, alignment, `0` and `#` flags like implementation for [`u32`].
+impl core::fmt::LowerHex for TypeId {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let hx = hex::HexView::new_lower(self.as_u32());
+        f.pad_integral(true, "0x", hx.as_str())
+    }
+}
+
+impl core::fmt::Binary for TypeId {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Binary::fmt(&self.as_u32(), f)
+    }
+}
+
+impl core::fmt::Octal for TypeId {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Octal::fmt(&self.as_u32(), f)
+    }
+}
+
+/// Parses hexadecimal representation produced by [`Display`](core::fmt::Display),
+/// [`UpperHex`](core::fmt::UpperHex) or [`LowerHex`](core::fmt::LowerHex).
+/// Accepts digits in any case, optional `0x` prefix and leading zeros.
+impl core::str::FromStr for TypeId {
+    type Err = ErrorParseTypeId;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        match hex::parse(digits.as_bytes()) {
+            Some(val) => Self::from_u32(val).map_err(ErrorParseTypeId::InvalidValue),
+            None => Err(ErrorParseTypeId::InvalidFormat),
+        }
+    }
+}
+
+impl CollisionReport {
+    #[cfg(not(feature = "unsafe_remove_duplicate_checks"))]
+    pub(crate) fn new() -> Self {
+        Self { _x: () }
+    }
+
+    /// Iterates over every [`TypeId`] shared by multiple registered types.
+    pub fn collisions(&self) -> impl Iterator<Item = Collision> + use<> {
+        implementation::iter_duplicate_type_ids().map(|type_id| Collision { type_id })
+    }
+
+    /// Returns report in [JSON format](./index.html#feature-json_report).
+    /// Available only if feature [`json_report`](./index.html#feature-json_report) is enabled.
+    #[cfg(feature = "json_report")]
+    #[must_use]
+    pub fn json(&self) -> impl core::fmt::Display + use<> {
+        json::JsonReport(implementation::iter_duplicate_type_ids)
     }
 }
 
+impl DuplicateTypeIds {
+    fn new() -> Self {
+        Self {
+            #[cfg(feature = "alloc")]
+            groups: implementation::iter_duplicate_type_ids()
+                .map(|type_id| (type_id, Collision { type_id }.report_types().collect()))
+                .collect(),
+            #[cfg(not(feature = "alloc"))]
+            _x: (),
+        }
+    }
+
+    /// Iterates over every [`TypeId`] shared by multiple registered types.
+    pub fn collisions(&self) -> impl Iterator<Item = Collision> + '_ {
+        #[cfg(feature = "alloc")]
+        let ids = self.groups.iter().map(|&(type_id, _)| type_id);
+        #[cfg(not(feature = "alloc"))]
+        let ids = implementation::iter_duplicate_type_ids();
+        ids.map(|type_id| Collision { type_id })
+    }
+
+    /// Iterates over every [`TypeId`] shared by multiple registered types
+    /// along with all types that have it.
+    /// Available only if feature `alloc` is enabled.
+    ///
+    /// Types are sorted by name if feature [`debug_type_name`](./index.html#feature-debug_type_name) is enabled.
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn groups(&self) -> impl ExactSizeIterator<Item = (TypeId, &[TypeEntry])> + '_ {
+        self.groups
+            .iter()
+            .map(|(type_id, types)| (*type_id, types.as_slice()))
+    }
+
+    /// Returns report in [JSON format](./index.html#feature-json_report).
+    /// Available only if feature [`json_report`](./index.html#feature-json_report) is enabled.
+    #[cfg(feature = "json_report")]
+    #[must_use]
+    pub fn json(&self) -> impl core::fmt::Display + use<> {
+        json::JsonReport(implementation::iter_duplicate_type_ids)
+    }
+}
+
+impl Collision {
+    /// Value shared by colliding types.
+    #[must_use]
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Iterates over registered types that have this [`TypeId`].
+    ///
+    /// Type names are available only if feature [`debug_type_name`](./index.html#feature-debug_type_name) is enabled.
+    pub fn types(&self) -> impl Iterator<Item = TypeEntry> + Clone + use<> {
+        let type_id = self.type_id;
+        implementation::pub_iter_registered_types().filter(move |e| e.type_id == type_id)
+    }
+
+    /// Types in order used by reports.
+    fn report_types(self) -> impl Iterator<Item = TypeEntry> + Clone {
+        #[cfg(feature = "debug_type_name")]
+        let types = self.sorted_types();
+        #[cfg(not(feature = "debug_type_name"))]
+        let types = self.types();
+        types
+    }
+
+    /// Same as [`Collision::types`] but ordered by type names for ease of testing.
+    /// Since we cannot allocate, we just look up next type every time.
+    #[cfg(feature = "debug_type_name")]
+    pub(crate) fn sorted_types(self) -> impl Iterator<Item = TypeEntry> + Clone + use<> {
+        let key = |(i, e): (usize, TypeEntry)| ((e.debug_type_name, i), e);
+        let mut prev = None;
+        core::iter::from_fn(move || {
+            let (k, entry) = self
+                .types()
+                .enumerate()
+                .map(key)
+                .filter(|&(k, _)| prev < Some(k))
+                .min_by_key(|&(k, _)| k)?;
+            prev = Some(k);
+            Some(entry)
+        })
+    }
+}
+
+impl core::fmt::Display for CollisionReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for collision in self.collisions() {
+            core::fmt::Display::fmt(&collision, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Display for DuplicateTypeIds {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        #[cfg(feature = "alloc")]
+        for (type_id, types) in self.groups() {
+            write_collision(f, type_id, types.iter().copied())?;
+        }
+        #[cfg(not(feature = "alloc"))]
+        for collision in self.collisions() {
+            core::fmt::Display::fmt(&collision, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for DuplicateTypeIds {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entries(self.collisions().map(|c| c.type_id))
+            .finish()
+    }
+}
+
+impl core::error::Error for DuplicateTypeIds {}
+
+impl core::fmt::Display for Collision {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_collision(f, self.type_id, self.report_types())
+    }
+}
+
+/// Writes line of report about single duplicate [`TypeId`].
+#[cfg_attr(
+    not(feature = "debug_type_name"),
+    allow(clippy::needless_pass_by_value)
+)]
+fn write_collision(
+    f: &mut core::fmt::Formatter<'_>,
+    type_id: TypeId,
+    types: impl Iterator<Item = TypeEntry> + Clone,
+) -> core::fmt::Result {
+    let hex_val = hex::HexView::new(type_id.as_u32());
+    f.write_str("small_type_id: Found duplicate type_id ")?;
+    f.write_str(hex_val.as_str())?;
+    #[cfg(not(feature = "debug_type_name"))]
+    {
+        let _ = types;
+        f.write_str(
+            r#". Consider enabling "debug_type_name" feature to display conflicting type names"#,
+        )?;
+    }
+    #[cfg(feature = "debug_type_name")]
+    {
+        let total = types.clone().count();
+        for (printed, entry) in types.enumerate() {
+            f.write_str(match printed {
+                0 => " for types ",
+                _ if printed + 1 == total => " and ",
+                _ => ", ",
+            })?;
+            f.write_str(entry.debug_type_name)?;
+        }
+    }
+    f.write_str(".\n")
+}
+
 impl core::fmt::Display for ErrorInvalidBytes {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -417,6 +1191,38 @@ impl core::fmt::Display for ErrorInvalidBytes {
 
 impl core::error::Error for ErrorInvalidBytes {}
 
+impl core::fmt::Display for ErrorInvalidTypeId {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Zero => "TypeId cannot be zero",
+            Self::MostSignificantBitSet => "most significant bit of TypeId must be zero",
+            Self::Unregistered => "TypeId doesn't belong to any registered type",
+        })
+    }
+}
+
+impl core::error::Error for ErrorInvalidTypeId {}
+
+impl core::fmt::Display for ErrorParseTypeId {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidFormat => f.write_str("invalid hexadecimal representation of TypeId"),
+            Self::InvalidValue(err) => core::fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl core::error::Error for ErrorParseTypeId {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::InvalidFormat => None,
+            Self::InvalidValue(err) => Some(err),
+        }
+    }
+}
+
 #[cfg(doctest)]
 #[doc = include_str!("../../ReadMe.md")]
 pub struct ReadmeDoctests;
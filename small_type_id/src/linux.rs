@@ -0,0 +1,252 @@
+//! Implementation of diagnostics that uses Linux system calls directly
+//! so it doesn't link to libc.
+
+use core::arch::asm;
+
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "riscv64"
+)))]
+compile_error!(
+    r#"Feature "linux_raw_syscalls" supports only x86_64, aarch64 and riscv64 architectures."#
+);
+
+const STDERR_FILENO: usize = 2;
+const SIGABRT: usize = 6;
+const EINTR: isize = 4;
+
+#[cfg(target_arch = "x86_64")]
+mod nr {
+    pub(super) const WRITE: usize = 1;
+    #[cfg(feature = "json_report")]
+    pub(super) const READ: usize = 0;
+    #[cfg(feature = "json_report")]
+    pub(super) const OPENAT: usize = 257;
+    #[cfg(feature = "json_report")]
+    pub(super) const CLOSE: usize = 3;
+    pub(super) const GETPID: usize = 39;
+    pub(super) const KILL: usize = 62;
+    pub(super) const EXIT_GROUP: usize = 231;
+}
+
+// aarch64 and riscv64 use generic syscall table.
+#[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+mod nr {
+    pub(super) const WRITE: usize = 64;
+    #[cfg(feature = "json_report")]
+    pub(super) const READ: usize = 63;
+    #[cfg(feature = "json_report")]
+    pub(super) const OPENAT: usize = 56;
+    #[cfg(feature = "json_report")]
+    pub(super) const CLOSE: usize = 57;
+    pub(super) const GETPID: usize = 172;
+    pub(super) const KILL: usize = 129;
+    pub(super) const EXIT_GROUP: usize = 94;
+}
+
+/// Returns result of system call, negative values are negated `errno`.
+///
+/// # Safety
+/// Arguments must be valid for selected system call.
+#[cfg(target_arch = "x86_64")]
+unsafe fn syscall3(nr: usize, a0: usize, a1: usize, a2: usize) -> isize {
+    let res: isize;
+    // SAFETY: Caller guarantees that arguments are valid.
+    unsafe {
+        asm!(
+            "syscall",
+            inlateout("rax") nr.cast_signed() => res,
+            in("rdi") a0,
+            in("rsi") a1,
+            in("rdx") a2,
+            lateout("rcx") _,
+            lateout("r11") _,
+            options(nostack),
+        );
+    }
+    res
+}
+
+/// Returns result of system call, negative values are negated `errno`.
+///
+/// # Safety
+/// Arguments must be valid for selected system call.
+#[cfg(target_arch = "aarch64")]
+unsafe fn syscall3(nr: usize, a0: usize, a1: usize, a2: usize) -> isize {
+    let res: isize;
+    // SAFETY: Caller guarantees that arguments are valid.
+    unsafe {
+        asm!(
+            "svc 0",
+            in("x8") nr,
+            inlateout("x0") a0.cast_signed() => res,
+            in("x1") a1,
+            in("x2") a2,
+            options(nostack),
+        );
+    }
+    res
+}
+
+/// Returns result of system call, negative values are negated `errno`.
+///
+/// # Safety
+/// Arguments must be valid for selected system call.
+#[cfg(target_arch = "riscv64")]
+unsafe fn syscall3(nr: usize, a0: usize, a1: usize, a2: usize) -> isize {
+    let res: isize;
+    // SAFETY: Caller guarantees that arguments are valid.
+    unsafe {
+        asm!(
+            "ecall",
+            in("a7") nr,
+            inlateout("a0") a0.cast_signed() => res,
+            in("a1") a1,
+            in("a2") a2,
+            options(nostack),
+        );
+    }
+    res
+}
+
+#[repr(transparent)]
+pub(crate) struct StdErr(usize);
+
+/// # Safety
+/// Must be called only once.
+/// While returned value in use, no other thread should access stderr.
+pub(crate) unsafe fn get_stderr() -> StdErr {
+    // Same as with libc, we just use unbuffered writes.
+    StdErr(STDERR_FILENO)
+}
+
+/// Returns file descriptor selected for JSON report or stderr.
+///
+/// Environment is read from `/proc/self/environ` because
+/// we don't have access to `environ` without libc.
+///
+/// # Safety
+/// Same as for [`get_stderr`].
+#[cfg(feature = "json_report")]
+pub(crate) unsafe fn get_json_report_output() -> StdErr {
+    match read_report_fd_var().and_then(|v| crate::json::parse_fd(v.as_slice())) {
+        Some(fd) => StdErr(fd as usize),
+        None => StdErr(STDERR_FILENO),
+    }
+}
+
+/// Value of environment variable that fits into small buffer.
+#[cfg(feature = "json_report")]
+struct ShortValue {
+    buffer: [u8; 16],
+    len: usize,
+}
+
+#[cfg(feature = "json_report")]
+impl ShortValue {
+    fn as_slice(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+}
+
+#[cfg(feature = "json_report")]
+fn read_report_fd_var() -> Option<ShortValue> {
+    const AT_FDCWD: usize = (-100isize).cast_unsigned();
+    const O_RDONLY_CLOEXEC: usize = 0o2_000_000;
+
+    let name = crate::json::REPORT_FD_ENV_VAR.to_bytes();
+    // SAFETY: Path is a valid C string.
+    let fd = unsafe {
+        syscall3(
+            nr::OPENAT,
+            AT_FDCWD,
+            c"/proc/self/environ".as_ptr() as usize,
+            O_RDONLY_CLOEXEC,
+        )
+    };
+    if fd < 0 {
+        return None;
+    }
+    let fd = fd.cast_unsigned();
+
+    // Entries are separated by zero bytes and can span multiple reads
+    // so we check them byte by byte.
+    let mut found = None;
+    let mut value = ShortValue {
+        buffer: [0; 16],
+        len: 0,
+    };
+    // Position in current entry or `None` if entry doesn't match.
+    let mut pos = Some(0);
+    let mut chunk = [0u8; 512];
+    'read: loop {
+        // SAFETY: Buffer is valid for writes of its length.
+        let res = unsafe { syscall3(nr::READ, fd, chunk.as_mut_ptr() as usize, chunk.len()) };
+        if res == -EINTR {
+            continue;
+        }
+        if res <= 0 {
+            break;
+        }
+        for &c in &chunk[..res.cast_unsigned()] {
+            if c == 0 {
+                if pos.is_some_and(|p| p > name.len()) {
+                    found = Some(value);
+                    break 'read;
+                }
+                pos = Some(0);
+                continue;
+            }
+            pos = match pos {
+                Some(p) if p < name.len() => (name[p] == c).then_some(p + 1),
+                Some(p) if p == name.len() => (c == b'=').then_some(p + 1),
+                Some(p) => {
+                    if value.len == value.buffer.len() {
+                        None
+                    } else {
+                        value.buffer[value.len] = c;
+                        value.len += 1;
+                        Some(p + 1)
+                    }
+                }
+                None => None,
+            };
+        }
+    }
+    // SAFETY: We opened this descriptor.
+    unsafe {
+        syscall3(nr::CLOSE, fd, 0, 0);
+    }
+    found
+}
+
+pub(crate) fn print_error(stderr: &mut StdErr, msg: &str) {
+    let mut rest = msg.as_bytes();
+    while !rest.is_empty() {
+        // SAFETY: Buffer is valid for reads of its length.
+        let res = unsafe { syscall3(nr::WRITE, stderr.0, rest.as_ptr() as usize, rest.len()) };
+        if res == -EINTR {
+            continue;
+        }
+        if res < 0 {
+            // We are trying to output diagnostic info on best effort basis.
+            return;
+        }
+        let written: usize = rest.len().min(res.unsigned_abs());
+        rest = &rest[written..];
+    }
+}
+
+pub(crate) fn terminate_current_process(_stderr: StdErr) -> ! {
+    // SAFETY: We are intentionally crashing so no problem.
+    unsafe {
+        // Emulate `abort` by sending SIGABRT to ourselves.
+        let pid = syscall3(nr::GETPID, 0, 0, 0);
+        syscall3(nr::KILL, pid.cast_unsigned(), SIGABRT, 0);
+        // Signal may be blocked or handled so exit with same code
+        // as shell reports for process killed by SIGABRT.
+        syscall3(nr::EXIT_GROUP, 128 + SIGABRT, 0, 0);
+    }
+    unreachable!()
+}
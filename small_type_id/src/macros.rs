@@ -25,20 +25,58 @@ macro_rules! private_macro_implement_type_id {
                     $seed,
                 )
             };
+
+            const __DEBUG_INFO: $crate::private::DebugInfo = $crate::private::DebugInfo::new(
+                ::core::concat!(::core::module_path!(), "::", $name_literal),
+                ::core::option_env!("CARGO_PKG_VERSION"),
+                $seed,
+            );
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(any(
+    feature = "unsafe_remove_duplicate_checks",
+    feature = "manual_registry"
+)))]
+#[cfg(not(small_type_id_link_section))]
+macro_rules! private_macro_register_type_id {
+    ($tname:ident, $name_literal:literal) => {
+        static ENTRY: $crate::private::TypeEntry = $crate::private::TypeEntry::new(
+            <$tname as $crate::HasTypeId>::__DEBUG_INFO,
+            <$tname as $crate::HasTypeId>::TYPE_ID,
+        );
+
+        static NODE: $crate::private::TypeNode<
+            { $crate::private::node_height(<$tname as $crate::HasTypeId>::TYPE_ID) },
+        > = $crate::private::TypeNode::new(<$tname as $crate::HasTypeId>::TYPE_ID);
+
+        $crate::private::ctor! {
+            #[ctor]
+            #[inline]
+            unsafe fn register_0kkvmqvjv2brioq8eilz7() {
+                unsafe {
+                    $crate::private::register_type(&ENTRY, &NODE);
+                }
+            }
         }
     };
 }
 
 #[doc(hidden)]
 #[macro_export]
-#[cfg(not(feature = "unsafe_dont_register_types"))]
-#[cfg(not(target_os = "windows"))]
-#[cfg(not(target_os = "linux"))]
+#[cfg(all(
+    feature = "unsafe_remove_duplicate_checks",
+    not(any(feature = "unsafe_dont_register_types", feature = "manual_registry"))
+))]
+#[cfg(not(small_type_id_link_section))]
 macro_rules! private_macro_register_type_id {
     ($tname:ident, $name_literal:literal) => {
         static ENTRY: $crate::private::TypeEntry = $crate::private::TypeEntry::new(
-            ::core::concat!(::core::module_path!(), "::", $name_literal),
-            <$tname as ::small_type_id::HasTypeId>::TYPE_ID,
+            <$tname as $crate::HasTypeId>::__DEBUG_INFO,
+            <$tname as $crate::HasTypeId>::TYPE_ID,
         );
 
         $crate::private::ctor! {
@@ -55,26 +93,476 @@ macro_rules! private_macro_register_type_id {
 
 #[doc(hidden)]
 #[macro_export]
-#[cfg(not(feature = "unsafe_dont_register_types"))]
-#[cfg(any(target_os = "windows", target_os = "linux"))]
+#[cfg(not(any(feature = "unsafe_dont_register_types", feature = "manual_registry")))]
+#[cfg(small_type_id_link_section)]
 macro_rules! private_macro_register_type_id {
     ($tname:ident, $name_literal:literal) => {
         #[unsafe(link_section=$crate::private::link_section_name!())]
         #[used]
         static ENTRY: $crate::private::TypeEntry = $crate::private::TypeEntry::new(
-            ::core::concat!(::core::module_path!(), "::", $name_literal),
-            <$tname as ::small_type_id::HasTypeId>::TYPE_ID,
+            <$tname as $crate::HasTypeId>::__DEBUG_INFO,
+            <$tname as $crate::HasTypeId>::TYPE_ID,
         );
+
+        #[unsafe(link_section=$crate::private::ids_link_section_name!())]
+        #[used]
+        static MIRRORED_ID: $crate::private::MirroredId =
+            $crate::private::MirroredId::new(<$tname as $crate::HasTypeId>::TYPE_ID);
     };
 }
 
 #[doc(hidden)]
 #[macro_export]
-#[cfg(feature = "unsafe_dont_register_types")]
+#[cfg(any(feature = "unsafe_dont_register_types", feature = "manual_registry"))]
 macro_rules! private_macro_register_type_id {
     ($tname:ident, $name_literal:literal) => {};
 }
 
+/// Generates test that checks uniqueness of registered [`TypeId`](crate::TypeId)s
+/// using [`verify_unique`](crate::verify_unique).
+///
+/// Test name can be passed as argument, default is `small_type_id_unique_type_ids`.
+///
+/// ```
+/// small_type_id::test_unique_type_ids!();
+/// small_type_id::test_unique_type_ids!(all_type_ids_are_unique);
+/// ```
+#[macro_export]
+macro_rules! test_unique_type_ids {
+    () => {
+        $crate::test_unique_type_ids!(small_type_id_unique_type_ids);
+    };
+    ($test_name:ident) => {
+        $crate::private::unique_type_ids_test!($test_name);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "manual_registry"))]
+macro_rules! private_macro_unique_type_ids_test {
+    ($test_name:ident) => {
+        #[test]
+        // MIRI unsupported until https://github.com/rust-lang/miri/issues/450 fixed
+        #[cfg_attr(miri, ignore)]
+        fn $test_name() {
+            if let ::core::result::Result::Err(err) = $crate::verify_unique() {
+                ::core::panic!("{}", err);
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "manual_registry")]
+macro_rules! private_macro_unique_type_ids_test {
+    ($test_name:ident) => {
+        #[test]
+        fn $test_name() {
+            if let ::core::result::Result::Err(err) = $crate::verify_unique() {
+                ::core::panic!("{}", err);
+            }
+        }
+    };
+}
+
+/// Defines table of registered types if feature
+/// [`manual_registry`](crate#feature-manual_registry) is enabled.
+///
+/// Must be invoked exactly once in every final binary, including test binaries,
+/// and must list every type that derives [`HasTypeId`](crate::HasTypeId).
+/// Listed types are returned by [`iter_registered_types`](crate::iter_registered_types)
+/// and verified on first use.
+///
+/// Since table is defined by binary, collision policy is passed to this macro
+/// instead of [`collision_policy!`](crate::collision_policy) using same syntax.
+///
+/// ```
+/// #[derive(small_type_id::HasTypeId)]
+/// struct A;
+/// #[derive(small_type_id::HasTypeId)]
+/// struct B;
+///
+/// small_type_id::registry! { A, B }
+/// # #[cfg(all(feature = "manual_registry", not(feature = "unsafe_dont_register_types")))]
+/// # assert_eq!(small_type_id::iter_registered_types().count(), 2);
+/// ```
+///
+/// ```
+/// # #[derive(small_type_id::HasTypeId)]
+/// # struct A;
+/// # #[derive(small_type_id::HasTypeId)]
+/// # struct B;
+/// small_type_id::registry! {
+///     collision_policy!(Panic);
+///     A, B
+/// }
+/// ```
+#[macro_export]
+macro_rules! registry {
+    (collision_policy!($($policy:tt)+); $($tname:path),* $(,)?) => {
+        $crate::private::define_registry!(
+            ::core::option::Option::Some($crate::private::policy_value!($($policy)+)),
+            $($tname),*
+        );
+    };
+    ($($tname:path),* $(,)?) => {
+        $crate::private::define_registry!(::core::option::Option::None, $($tname),*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_macro_define_registry {
+    ($policy:expr, $($tname:path),*) => {
+        const _: () = {
+            static ENTRIES: &[$crate::TypeEntry] = &[$(
+                $crate::private::manual_entry(
+                    <$tname as $crate::HasTypeId>::__DEBUG_INFO,
+                    <$tname as $crate::HasTypeId>::TYPE_ID,
+                )
+            ),*];
+            static REGISTRY: $crate::private::ManualRegistry =
+                $crate::private::ManualRegistry::new(ENTRIES, $policy);
+
+            #[unsafe(export_name = ::core::concat!(
+                "small_type_id_manual_registry_",
+                $crate::private::small_type_id_version!()
+            ))]
+            fn registry() -> &'static $crate::private::ManualRegistry {
+                &REGISTRY
+            }
+        };
+    };
+}
+
+/// Selects [`CollisionPolicy`](crate::CollisionPolicy) that is applied when duplicate [`TypeId`](crate::TypeId)s are found.
+///
+/// Only the final binary crate may invoke this macro, at most once.
+/// It defines an exported symbol so second invocation usually fails to link.
+/// If policy is selected more than once anyway, [`Abort`](crate::CollisionPolicy::Abort) is used.
+///
+/// On platforms other than Linux and Windows, or if `force_ctor_registration` feature is enabled,
+/// policy is applied only to duplicates found after constructor, generated by this macro, had run.
+/// Since order of constructors is not specified, earlier duplicates are handled using default policy.
+///
+/// Policies that terminate execution can be selected without `unsafe`:
+///
+/// ```
+/// # #[cfg(not(feature = "manual_registry"))]
+/// small_type_id::collision_policy!(Panic);
+/// ```
+///
+/// Policies that allow to continue execution with duplicate `TypeId`s
+/// require `unsafe` block because any code that relies on uniqueness of them becomes unsound.
+/// Macro expands to call of `unsafe fn` which is wrapped into that block:
+///
+/// ```
+/// fn hook(report: &small_type_id::CollisionReport) {
+///     for collision in report.collisions() {
+///         eprintln!("Collision of {}", collision.type_id());
+///     }
+///     std::process::abort();
+/// }
+///
+/// # #[cfg(not(feature = "manual_registry"))]
+/// small_type_id::collision_policy!(unsafe { Hook(hook) });
+/// ```
+///
+/// ```compile_fail
+/// small_type_id::collision_policy!(Warn);
+/// ```
+///
+/// ```compile_fail
+/// small_type_id::collision_policy!(not_unsafe { Warn });
+/// ```
+///
+/// Libraries must not invoke this macro because it would conflict with policy selected by binary
+/// or by other library:
+///
+/// ```compile_fail
+/// mod some_library {
+///     small_type_id::collision_policy!(Panic);
+/// }
+///
+/// small_type_id::collision_policy!(Abort);
+/// ```
+#[macro_export]
+macro_rules! collision_policy {
+    ($($policy:tt)+) => {
+        $crate::private::set_collision_policy!($crate::private::policy_value!($($policy)+));
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_macro_policy_value {
+    (Abort) => {
+        $crate::CollisionPolicy::Abort
+    };
+    (Panic) => {
+        $crate::CollisionPolicy::Panic
+    };
+    (unsafe { Warn }) => {
+        unsafe { $crate::private::continue_on_collisions($crate::CollisionPolicy::Warn) }
+    };
+    (unsafe { Hook($hook:expr) }) => {
+        unsafe { $crate::private::continue_on_collisions($crate::CollisionPolicy::Hook($hook)) }
+    };
+}
+
+/// Builds [`DispatchTable`](crate::dispatch::DispatchTable) that maps types to values.
+///
+/// Can be used to initialize constants and statics.
+/// Fails to compile if some types have same [`TypeId`](crate::TypeId).
+///
+/// ```
+/// use small_type_id::dispatch::DispatchTable;
+/// use small_type_id::HasTypeId as _;
+///
+/// #[derive(small_type_id::HasTypeId)]
+/// struct A;
+/// #[derive(small_type_id::HasTypeId)]
+/// struct B;
+///
+/// const NAMES: DispatchTable<&str, 2> = small_type_id::dispatch_table! {
+///     A => "a",
+///     B => "b",
+/// };
+/// assert_eq!(NAMES.get(B::TYPE_ID), Some(&"b"));
+/// ```
+#[macro_export]
+macro_rules! dispatch_table {
+    ($($tname:path => $value:expr),* $(,)?) => {
+        $crate::dispatch::DispatchTable::new([$(
+            (<$tname as $crate::HasTypeId>::TYPE_ID, $value)
+        ),*])
+    };
+}
+
+/// Compares [`TypeId`](crate::TypeId) with ids of several types.
+///
+/// Expands to `match` on values of [`TypeId::as_u32`](crate::TypeId::as_u32),
+/// so compiler can optimize it like any other `match` on integers.
+/// Every arm lists one or more types separated by `|`
+/// and must be followed by comma unless its body is a block. Last arm must be `_`.
+/// Fails to compile if some type is listed twice or if some types have same `TypeId`.
+///
+/// ```
+/// use small_type_id::HasTypeId as _;
+/// use small_type_id::TypeId;
+///
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Circle;
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Ellipse;
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Square;
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Triangle;
+///
+/// fn corners(id: TypeId) -> Option<u32> {
+///     small_type_id::match_type!(id, {
+///         Circle | Ellipse => Some(0),
+///         Square => Some(4),
+///         Triangle => {
+///             let sides = 3;
+///             Some(sides)
+///         }
+///         _ => None,
+///     })
+/// }
+///
+/// assert_eq!(corners(Ellipse::TYPE_ID), Some(0));
+/// assert_eq!(corners(Square::TYPE_ID), Some(4));
+/// assert_eq!(corners(Triangle::TYPE_ID), Some(3));
+/// assert_eq!(corners(TypeId::from_u32(42).unwrap()), None);
+/// ```
+///
+/// ```compile_fail
+/// # use small_type_id::HasTypeId as _;
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Circle;
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Square;
+///
+/// small_type_id::match_type!(Circle::TYPE_ID, {
+///     Circle => 1,
+///     Square | Circle => 2,
+///     _ => 3,
+/// });
+/// ```
+#[macro_export]
+macro_rules! match_type {
+    ($id:expr, { $($arms:tt)* }) => {
+        $crate::private::match_type_arms!($id; []; []; $($arms)*)
+    };
+}
+
+// Accumulates ids and patterns of arms until `_` is reached
+// because repetition of paths cannot be followed by `_`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_macro_match_type_arms {
+    ($id:expr; [$($ids:expr),*]; [$($arms:tt)*]; _ => $default:expr $(,)?) => {{
+        const _: () = ::core::assert!(
+            $crate::konst::is_unique(&[$($ids),*]),
+            "Duplicate TypeId in match_type!"
+        );
+        match $crate::TypeId::as_u32($id) {
+            $($arms)*
+            _ => $default,
+        }
+    }};
+    // Like in regular `match`, arms with block bodies don't need comma.
+    ($id:expr; [$($ids:expr),*]; [$($arms:tt)*]; $($tname:path)|+ => $body:block, $($rest:tt)*) => {
+        $crate::private::match_type_arms!(
+            $id;
+            [$($ids,)* $(<$tname as $crate::HasTypeId>::TYPE_ID),+];
+            [$($arms)* $(<$tname as $crate::private::RawTypeId>::RAW)|+ => $body,];
+            $($rest)*
+        )
+    };
+    ($id:expr; [$($ids:expr),*]; [$($arms:tt)*]; $($tname:path)|+ => $body:block $($rest:tt)*) => {
+        $crate::private::match_type_arms!(
+            $id;
+            [$($ids,)* $(<$tname as $crate::HasTypeId>::TYPE_ID),+];
+            [$($arms)* $(<$tname as $crate::private::RawTypeId>::RAW)|+ => $body,];
+            $($rest)*
+        )
+    };
+    ($id:expr; [$($ids:expr),*]; [$($arms:tt)*]; $($tname:path)|+ => $body:expr, $($rest:tt)*) => {
+        $crate::private::match_type_arms!(
+            $id;
+            [$($ids,)* $(<$tname as $crate::HasTypeId>::TYPE_ID),+];
+            [$($arms)* $(<$tname as $crate::private::RawTypeId>::RAW)|+ => $body,];
+            $($rest)*
+        )
+    };
+}
+
+/// Expands to [`TypeList`](crate::type_list::TypeList) type that lists up to 16 types.
+///
+/// Duplicate [`TypeId`](crate::TypeId)s are rejected only when ids of list are evaluated
+/// because macro in type position cannot declare items that check them.
+/// Use [`define_type_list!`](crate::define_type_list) instead, which rejects them at declaration.
+#[deprecated(note = "use `define_type_list!` which rejects duplicate types at declaration")]
+#[macro_export]
+macro_rules! TypeList {
+    ($($tname:ty),* $(,)?) => {
+        $crate::type_list::TypeList<($($tname,)*), { $crate::private::count_types!($($tname),*) }>
+    };
+}
+
+/// Declares alias of [`TypeList`](crate::type_list::TypeList) type that lists up to 16 types
+/// and fails to compile if listed types have same [`TypeId`](crate::TypeId),
+/// even if alias is never used.
+///
+/// ```
+/// #[derive(small_type_id::HasTypeId)]
+/// struct A;
+/// #[derive(small_type_id::HasTypeId)]
+/// struct B;
+///
+/// small_type_id::define_type_list! {
+///     /// Types that are stored together.
+///     pub type Both = (A, B);
+/// }
+/// assert_eq!(Both::index_of::<B>(), Some(1));
+/// ```
+///
+/// ```compile_fail
+/// #[derive(small_type_id::HasTypeId)]
+/// struct A;
+///
+/// small_type_id::define_type_list! {
+///     type Twice = (A, A);
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_type_list {
+    ($(#[$attr:meta])* $vis:vis type $name:ident = ($($tname:ty),* $(,)?);) => {
+        $(#[$attr])*
+        $vis type $name = $crate::type_list::TypeList<
+            ($($tname,)*),
+            { $crate::private::count_types!($($tname),*) },
+        >;
+
+        const _: () = {
+            let _ = $name::IDS;
+        };
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_macro_count_types {
+    () => {
+        0
+    };
+    ($head:ty $(, $tail:ty)*) => {
+        1 + $crate::private::count_types!($($tail),*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "manual_registry"))]
+#[cfg(small_type_id_link_section)]
+macro_rules! private_macro_set_collision_policy {
+    ($policy:expr) => {
+        const _: () = {
+            #[unsafe(export_name = $crate::private::collision_policy_symbol_name!())]
+            static ONLY_ONE_POLICY_ALLOWED: u8 = 0;
+
+            static POLICY: $crate::private::CollisionPolicyRecord =
+                $crate::private::CollisionPolicyRecord::new($policy);
+
+            #[unsafe(link_section = $crate::private::collision_policy_section_name!())]
+            #[used]
+            static POLICY_REF: &$crate::private::CollisionPolicyRecord = &POLICY;
+        };
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "manual_registry")]
+macro_rules! private_macro_set_collision_policy {
+    ($policy:expr) => {
+        ::core::compile_error!(
+            "Collision policy must be passed to `registry!` macro if `manual_registry` feature is enabled."
+        );
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "manual_registry"))]
+#[cfg(not(small_type_id_link_section))]
+macro_rules! private_macro_set_collision_policy {
+    ($policy:expr) => {
+        const _: () = {
+            #[unsafe(export_name = $crate::private::collision_policy_symbol_name!())]
+            static ONLY_ONE_POLICY_ALLOWED: u8 = 0;
+
+            static POLICY: $crate::private::CollisionPolicyRecord =
+                $crate::private::CollisionPolicyRecord::new($policy);
+
+            $crate::private::ctor! {
+                #[ctor]
+                #[inline]
+                unsafe fn set_collision_policy_0kkvmqvjv2brioq8eilz7() {
+                    unsafe {
+                        $crate::private::set_collision_policy(&POLICY);
+                    }
+                }
+            }
+        };
+    };
+}
+
 // This macro is needed to make every link_section attribute distinct
 // in case of multiple crate versions being linked.
 // We cannot just use `env!("CARGO_PKG_VERSION")` because it works on caller site.
@@ -91,7 +579,7 @@ macro_rules! private_macro_small_type_id_version {
 
 #[doc(hidden)]
 #[macro_export]
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
 macro_rules! private_macro_link_section_name {
     () => {
         ::core::concat!(
@@ -104,7 +592,7 @@ macro_rules! private_macro_link_section_name {
 
 #[doc(hidden)]
 #[macro_export]
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
 macro_rules! private_macro_link_section_name {
     () => {
         ::core::concat!(
@@ -116,13 +604,128 @@ macro_rules! private_macro_link_section_name {
 
 #[doc(hidden)]
 #[macro_export]
-#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "windows",
+    feature = "linker_script_registration"
+)))]
 macro_rules! private_macro_link_section_name {
     () => {
         ::core::compile_error!("Usage of link section is not supported on current platform (yet).")
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+macro_rules! private_macro_ids_link_section_name {
+    () => {
+        ::core::concat!(
+            "smltidrs_ids$",
+            $crate::private::small_type_id_version!(),
+            "_b"
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
+macro_rules! private_macro_ids_link_section_name {
+    () => {
+        ::core::concat!("smltidrs_ids", $crate::private::small_type_id_version!(),)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "windows",
+    feature = "linker_script_registration"
+)))]
+macro_rules! private_macro_ids_link_section_name {
+    () => {
+        ::core::compile_error!("Usage of link section is not supported on current platform (yet).")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(target_os = "windows", not(feature = "linker_script_registration")))]
+macro_rules! private_macro_collision_policy_section_name {
+    () => {
+        "smltidrs_collision_policies$b"
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(target_os = "linux", not(feature = "linker_script_registration")))]
+macro_rules! private_macro_collision_policy_section_name {
+    () => {
+        "smltidrs_collision_policies"
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "windows",
+    feature = "linker_script_registration"
+)))]
+macro_rules! private_macro_collision_policy_section_name {
+    () => {
+        ::core::compile_error!("Usage of link section is not supported on current platform (yet).")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "linker_script_registration")]
+macro_rules! private_macro_link_section_name {
+    () => {
+        ::core::concat!(
+            ".small_type_id_registry.entries.",
+            $crate::private::small_type_id_version!(),
+            ".b"
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "linker_script_registration")]
+macro_rules! private_macro_ids_link_section_name {
+    () => {
+        ::core::concat!(
+            ".small_type_id_ids.",
+            $crate::private::small_type_id_version!(),
+            ".b"
+        )
+    };
+}
+
+// Linking fails if this symbol is defined twice so only one policy can be selected.
+// It doesn't depend on version because policy is shared by all versions.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_macro_collision_policy_symbol_name {
+    () => {
+        "small_type_id_collision_policy"
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "linker_script_registration")]
+macro_rules! private_macro_collision_policy_section_name {
+    () => {
+        ".small_type_id_registry.policies"
+    };
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -0,0 +1,24 @@
+//! Fallback for targets without operating system, e.g. firmware.
+//! There is no stderr so reports are not written anywhere.
+
+pub(crate) struct StdErr(());
+
+/// # Safety
+/// Must be called only once.
+pub(crate) unsafe fn get_stderr() -> StdErr {
+    StdErr(())
+}
+
+/// # Safety
+/// Same as for [`get_stderr`].
+#[cfg(feature = "json_report")]
+pub(crate) unsafe fn get_json_report_output() -> StdErr {
+    StdErr(())
+}
+
+pub(crate) fn print_error(_stderr: &mut StdErr, _msg: &str) {}
+
+pub(crate) fn terminate_current_process(_stderr: StdErr) -> ! {
+    // Leave it to panic handler of firmware.
+    panic!("small_type_id: Found duplicate type_ids")
+}
@@ -0,0 +1,138 @@
+//! Implementations of traits of `serde` crate.
+//!
+//! Available only if feature [`serde`](crate#feature-serde) is enabled.
+//!
+//! Human readable formats use same hexadecimal string as [`Display`](core::fmt::Display)
+//! implementation of [`TypeId`], other formats use [`u32`].
+//! Deserialization checks that value conforms to invariants of [`TypeId`].
+
+use core::fmt;
+
+use ::serde::de::{self, Deserializer, Unexpected, Visitor};
+use ::serde::ser::{SerializeStruct as _, Serializer};
+use ::serde::{Deserialize, Serialize};
+
+use crate::{ErrorInvalidTypeId, ErrorParseTypeId, TypeEntry, TypeId};
+
+impl Serialize for TypeId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u32(self.as_u32())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TypeId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(TypeIdVisitor)
+        } else {
+            deserializer.deserialize_u32(TypeIdVisitor)
+        }
+    }
+}
+
+/// Accepts both representations so values can be converted between formats.
+struct TypeIdVisitor;
+
+impl Visitor<'_> for TypeIdVisitor {
+    type Value = TypeId;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TypeId as hexadecimal string or u32")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<TypeId, E> {
+        let val = u32::try_from(v).map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))?;
+        TypeId::from_u32(val).map_err(|err| invalid_value(Unexpected::Unsigned(v), err))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<TypeId, E> {
+        match u64::try_from(v) {
+            Ok(v) => self.visit_u64(v),
+            Err(_) => Err(E::invalid_value(Unexpected::Signed(v), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<TypeId, E> {
+        v.parse().map_err(|err| match err {
+            ErrorParseTypeId::InvalidValue(err) => invalid_value(Unexpected::Str(v), err),
+            _ => E::invalid_value(Unexpected::Str(v), &self),
+        })
+    }
+}
+
+fn invalid_value<E: de::Error>(unexp: Unexpected<'_>, err: ErrorInvalidTypeId) -> E {
+    struct Expected(ErrorInvalidTypeId);
+
+    impl de::Expected for Expected {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "valid TypeId ({})", self.0)
+        }
+    }
+
+    E::invalid_value(unexp, &Expected(err))
+}
+
+/// Serializes fields available with enabled features.
+impl Serialize for TypeEntry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(not(feature = "debug_type_name"))]
+        const LEN: usize = 1;
+        #[cfg(feature = "debug_type_name")]
+        const LEN: usize = 4;
+
+        let mut s = serializer.serialize_struct("TypeEntry", LEN)?;
+        s.serialize_field("type_id", &self.type_id)?;
+        #[cfg(feature = "debug_type_name")]
+        {
+            s.serialize_field("debug_type_name", self.debug_type_name)?;
+            s.serialize_field("debug_crate_version", self.debug_crate_version)?;
+            s.serialize_field("debug_seed", &self.debug_seed)?;
+        }
+        s.end()
+    }
+}
+
+/// Deserializes [`TypeId`] only if it belongs to registered type.
+///
+/// Intended for use with `#[serde(with = "small_type_id::serde::registered")]`
+/// on fields that come from untrusted input.
+/// Uses [`TypeId::from_registered_bytes`] so it has same cost and limitations.
+///
+/// ```
+/// # use small_type_id::HasTypeId as _;
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Message;
+///
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Header {
+///     #[serde(with = "small_type_id::serde::registered")]
+///     kind: small_type_id::TypeId,
+/// }
+/// ```
+pub mod registered {
+    use super::{Deserialize as _, Deserializer, Serialize as _, Serializer, TypeId, de};
+
+    /// Same as [`Serialize`](::serde::Serialize) implementation of [`TypeId`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error of serializer.
+    pub fn serialize<S: Serializer>(type_id: &TypeId, serializer: S) -> Result<S::Ok, S::Error> {
+        type_id.serialize(serializer)
+    }
+
+    /// Same as [`Deserialize`](::serde::Deserialize) implementation of [`TypeId`]
+    /// but also checks that [`TypeId`] belongs to registered type.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if value is not a valid [`TypeId`] of registered type.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TypeId, D::Error> {
+        let type_id = TypeId::deserialize(deserializer)?;
+        TypeId::from_registered_bytes(type_id.to_bytes()).map_err(de::Error::custom)
+    }
+}
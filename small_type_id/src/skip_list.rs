@@ -1,6 +1,7 @@
 use core::marker::PhantomData;
 use core::ptr::{NonNull, null_mut};
 
+#[cfg(test)]
 use rand::Rng;
 
 #[repr(C)] // repr C ensures that value and nexts[0] are close.
@@ -12,13 +13,34 @@ pub struct SkipListNode<T, const HEIGHT: usize> {
     /// 0 is lowest level (where every entry is linked),
     /// HEIGH-1 is biggest level (where only very rare linkage).
     /// This should make iterating over
-    nexts: [*mut SkipListNode<T, HEIGHT>; HEIGHT],
+    nexts: [Link<T>; HEIGHT],
+}
+
+/// Pointer to node with tower of any height.
+///
+/// Nodes of all heights have same layout except length of `nexts`
+/// so it is typed as node of smallest height
+/// and links above 0 are accessed only using [`link_at`].
+type Link<T> = *mut SkipListNode<T, 1>;
+
+/// Returns pointer to link of `node` at `level`.
+///
+/// # Safety
+///
+/// `node` must point to node with tower higher than `level`.
+unsafe fn link_at<T>(node: Link<T>, level: usize) -> *mut Link<T> {
+    // Never create reference to `nexts` because its length is a lie.
+    unsafe { (&raw mut (*node).nexts).cast::<Link<T>>().add(level) }
 }
 
 /// It is intrusive skiplist.
+///
+/// Nodes may have towers of different heights, up to `HEIGHT`.
+/// Since most of nodes have only lowest levels,
+/// it allows to avoid reserving full tower for every node.
 pub(crate) struct SkipList<'element, T, const HEIGHT: usize> {
     // It points to first element but doesn't contain any.
-    prehead: [*mut SkipListNode<T, HEIGHT>; HEIGHT],
+    prehead: [Link<T>; HEIGHT],
     // This enables borrow checker to know that we borrow our nodes.
     _marker: PhantomData<&'element mut T>,
 }
@@ -50,6 +72,7 @@ impl<T, const HEIGHT: usize> SkipList<'_, T, HEIGHT> {
         }
     }
 
+    #[cfg(test)]
     pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
         let mut p = self.prehead[0];
         core::iter::from_fn(move || unsafe {
@@ -68,33 +91,58 @@ impl<'element, T, const HEIGHT: usize> SkipList<'element, T, HEIGHT>
 where
     T: Ord + Copy,
 {
+    /// Inserts entry linking it on randomly selected number of levels.
+    #[cfg(test)]
+    pub(crate) fn insert<const N: usize>(
+        &mut self,
+        entry: &'element mut SkipListNode<T, N>,
+        rng: &mut impl Rng,
+    ) -> InsertResult<T> {
+        let level = determine_level(rng, N - 1);
+        self.insert_at_level(entry, level)
+    }
+
+    /// Inserts entry linking it on every level of its tower.
+    ///
+    /// Caller is responsible for choosing heights of towers randomly,
+    /// with every next level half as likely as previous.
+    pub(crate) fn insert_full_tower<const N: usize>(
+        &mut self,
+        entry: &'element mut SkipListNode<T, N>,
+    ) -> InsertResult<T> {
+        let level = u8::try_from(N - 1).expect("Height of skip list must fit into u8");
+        self.insert_at_level(entry, level)
+    }
+
     // Note that entries cannot be borrowed after being inserted
     // because they are borrowed mutably.
     // This allows this function to not be unsafe.
-    pub(crate) fn insert(
+    fn insert_at_level<const N: usize>(
         &mut self,
-        entry: &'element mut SkipListNode<T, HEIGHT>,
-        rng: &mut impl Rng,
+        entry: &'element mut SkipListNode<T, N>,
+        level: u8,
     ) -> InsertResult<T> {
+        const { assert!(N <= HEIGHT, "Tower must fit into skip list") };
         debug_assert_eq!(
             entry.nexts,
-            [null_mut(); HEIGHT],
+            [null_mut(); N],
             "Must be inserted only once and only to one skiplist"
         );
         debug_assert_eq!(entry.level, 0);
+        debug_assert!(usize::from(level) < N);
 
-        entry.level = determine_level(rng, HEIGHT - 1);
+        entry.level = level;
 
         let mut prev_val: Option<T> = None;
         // Pointers to values that are <= than entry.
-        let mut prevs: [NonNull<*mut SkipListNode<T, HEIGHT>>; HEIGHT] =
-            core::array::from_fn(unsafe {
-                let start = self.prehead.as_mut_ptr();
-                move |i| NonNull::new(start.add(i)).unwrap()
-            });
-        let mut current_place: NonNull<*mut SkipListNode<T, HEIGHT>> = prevs[HEIGHT - 1];
+        let mut prevs: [NonNull<Link<T>>; HEIGHT] = core::array::from_fn(unsafe {
+            let start = self.prehead.as_mut_ptr();
+            move |i| NonNull::new(start.add(i)).unwrap()
+        });
+        let mut current_place: NonNull<Link<T>> = prevs[HEIGHT - 1];
         for level in (0..HEIGHT).rev() {
             // SAFETY: Pointers in list can be added only using `insert` call so it must be valid.
+            // Node is linked at `level` only if its tower is high enough.
             // Unique borrow of every `entry` arg is enforced by borrow checker.
             unsafe {
                 loop {
@@ -106,7 +154,7 @@ where
                         break;
                     }
                     prev_val = Some((*nxt).value);
-                    current_place = NonNull::new((*nxt).nexts.as_mut_ptr().add(level)).unwrap();
+                    current_place = NonNull::new(link_at(nxt, level)).unwrap();
                 }
                 prevs[level] = current_place;
                 if level > 0 {
@@ -121,25 +169,25 @@ where
             InsertResult::Unique
         };
 
-        entry.nexts = unsafe { prevs.map(|x| *x.as_ptr()) };
         let max_lvl: usize = entry.level.into();
-        let p: *mut _ = entry;
-        for level in 0..HEIGHT {
-            if level > max_lvl {
-                break;
-            }
+        for (next, prev) in entry.nexts.iter_mut().zip(&prevs).take(max_lvl + 1) {
+            *next = unsafe { *prev.as_ptr() };
+        }
+        let p: Link<T> = core::ptr::from_mut(entry).cast();
+        for prev in prevs.iter().take(max_lvl + 1) {
             unsafe {
-                *prevs[level].as_ptr() = p;
+                *prev.as_ptr() = p;
             }
         }
         res
     }
 }
 
+#[cfg(test)]
 fn determine_level(rng: &mut impl Rng, max: usize) -> u8 {
-    debug_assert!(usize::from(u8::MAX) > max);
+    let max = u8::try_from(max).expect("Height of skip list must fit into u8");
     let mut level = 0;
-    while level < max as u8 && rng.random_bool(0.5) {
+    while level < max && rng.random_bool(0.5) {
         level += 1;
     }
     level
@@ -182,7 +230,7 @@ mod tests {
     }
 
     fn print_skiplist<const HEIGHT: usize>(skiplist: &SkipList<u32, HEIGHT>) -> String {
-        let node2pos: HashMap<*mut SkipListNode<u32, HEIGHT>, usize> = {
+        let node2pos: HashMap<Link<u32>, usize> = {
             let mut n = HashMap::new();
             let mut it = skiplist.prehead[0];
             let mut i = 0;
@@ -205,9 +253,9 @@ mod tests {
             while !it.is_null() {
                 let pos = node2pos[&it];
                 let extend_len = (pos as isize - curr_pos - 1) as usize;
-                res.extend(std::iter::repeat('-').take((delim.len() + 2) * extend_len));
+                res.extend(std::iter::repeat_n('-', (delim.len() + 2) * extend_len));
                 write!(&mut res, "{}{:2}", delim, unsafe { (*it).value }).unwrap();
-                it = unsafe { (*it).nexts[level] };
+                it = unsafe { *link_at(it, level) };
                 curr_pos = pos as isize;
             }
             res.push('\n');
@@ -230,7 +278,7 @@ mod tests {
     #[test]
     fn validate_heaps_algorithm() {
         fn factorial(n: usize) -> usize {
-            (2..=n).fold(1, std::ops::Mul::mul)
+            (2..=n).product()
         }
         // Miri is faster if we work with arrays.
         fn make_set<const N: usize>() -> HashSet<[u32; N]> {
@@ -262,7 +310,7 @@ mod tests {
             let mut nodes: Vec<SkipListNode<u32, 3>> =
                 perm.iter().copied().map(SkipListNode::new).collect();
             let mut rng = SmallRng::seed_from_u64(64646997);
-            let mut list = SkipList::new();
+            let mut list: SkipList<u32, 3> = SkipList::new();
             for node in nodes.iter_mut() {
                 list.insert(node, &mut rng);
             }
@@ -281,7 +329,7 @@ mod tests {
             let nums = [
                 9, 16, 13, 0, 6, 10, 14, 1, 4, 15, 17, 3, 18, 19, 5, 2, 12, 7, 8, 11,
             ];
-            let mut nodes = nums.map(SkipListNode::new);
+            let mut nodes = nums.map(SkipListNode::<u32, 4>::new);
             let mut list: SkipList<u32, 4> = SkipList::new();
             let mut rng = SmallRng::seed_from_u64(64646997);
             for node in nodes.iter_mut() {
@@ -301,7 +349,7 @@ mod tests {
                 14, 15, 26, 39, 0, 20, 16, 27, 45, 21, 10, 30, 49, 28, 3, 41, 29, 7, 2, 4, 38, 44,
                 46, 34, 22, 9, 31, 40,
             ];
-            let mut nodes = nums.map(SkipListNode::new);
+            let mut nodes = nums.map(SkipListNode::<u32, 4>::new);
             let mut list: SkipList<u32, 4> = SkipList::new();
             let mut rng = SmallRng::seed_from_u64(64646997);
             for node in nodes.iter_mut() {
@@ -310,4 +358,31 @@ mod tests {
             assert!(is_sorted(&list));
         }
     }
+
+    #[test]
+    fn test_towers_of_different_heights() {
+        let nums = [
+            9, 16, 13, 0, 6, 10, 14, 1, 4, 15, 17, 3, 18, 19, 5, 2, 12, 7, 8, 11,
+        ];
+        let mut low = nums.map(|x| SkipListNode::<u32, 1>::new(x * 3));
+        let mut mid = nums.map(|x| SkipListNode::<u32, 2>::new(x * 3 + 1));
+        let mut high = nums.map(|x| SkipListNode::<u32, 4>::new(x * 3 + 2));
+        let mut dup = SkipListNode::<u32, 3>::new(7);
+        let mut list: SkipList<u32, 4> = SkipList::new();
+        for ((l, m), h) in low.iter_mut().zip(mid.iter_mut()).zip(high.iter_mut()) {
+            assert!(matches!(list.insert_full_tower(h), InsertResult::Unique));
+            assert!(matches!(list.insert_full_tower(l), InsertResult::Unique));
+            assert!(matches!(list.insert_full_tower(m), InsertResult::Unique));
+        }
+        assert!(matches!(
+            list.insert_full_tower(&mut dup),
+            InsertResult::Duplicate(7)
+        ));
+        assert!(is_sorted(&list));
+        assert_eq!(list.iter().count(), nums.len() * 3 + 1);
+        let s = print_skiplist(&list);
+        let levels: Vec<usize> = s.lines().map(|l| l.matches("->").count()).collect();
+        let n = nums.len();
+        assert_eq!(levels, [n, n + 1, n * 2 + 1, n * 3 + 1]);
+    }
 }
@@ -0,0 +1,456 @@
+//! Search in slices of [`TypeId`]s.
+//!
+//! Functions compare 8 [`TypeId`]s at once using SIMD instructions if target supports them:
+//! AVX2 or SSE2 on x86 and x86-64, and NEON on `AArch64`.
+//! Since crate doesn't depend on `std`, instruction set is selected at compile time,
+//! e.g. AVX2 is used only if it is enabled using `-C target-feature=+avx2` or `-C target-cpu`.
+//! On other targets, scalar code is used.
+//!
+//! ```
+//! use small_type_id::slice;
+//! use small_type_id::HasTypeId as _;
+//!
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Position;
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Velocity;
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Health;
+//!
+//! let mut archetype = [Position::TYPE_ID, Velocity::TYPE_ID, Health::TYPE_ID];
+//! assert!(slice::contains(&archetype, Velocity::TYPE_ID));
+//! assert_eq!(slice::position(&archetype, Health::TYPE_ID), Some(2));
+//!
+//! archetype.sort();
+//! let mut query = [Health::TYPE_ID, Position::TYPE_ID];
+//! query.sort();
+//! assert!(slice::is_subset_sorted(&query, &archetype));
+//!
+//! let mut buf = [Position::TYPE_ID; 2];
+//! assert_eq!(slice::intersect_sorted(&query, &archetype, &mut buf), &query);
+//! ```
+
+use core::cmp::Ordering;
+
+use crate::TypeId;
+
+/// Number of [`TypeId`]s compared at once.
+const BLOCK: usize = 8;
+
+/// Returns `true` if `ids` contain `id`.
+#[must_use]
+#[inline]
+pub fn contains(ids: &[TypeId], id: TypeId) -> bool {
+    position(ids, id).is_some()
+}
+
+/// Returns index of first occurrence of `id` in `ids`.
+#[must_use]
+pub fn position(ids: &[TypeId], id: TypeId) -> Option<usize> {
+    let mut rest = ids;
+    let mut offset = 0;
+    while let Some((block, tail)) = rest.split_first_chunk::<BLOCK>() {
+        let mask = simd::eq_mask(block, id);
+        if mask != 0 {
+            return Some(offset + mask.trailing_zeros() as usize);
+        }
+        offset += BLOCK;
+        rest = tail;
+    }
+    rest.iter().position(|&x| x == id).map(|i| offset + i)
+}
+
+/// Returns number of elements of sorted `ids` that are less than `id`.
+fn count_less_sorted(ids: &[TypeId], id: TypeId) -> usize {
+    // Dense inputs usually don't skip anything.
+    if ids.first().is_none_or(|&x| x >= id) {
+        return 0;
+    }
+    let mut rest = ids;
+    let mut count = 0;
+    while let Some((block, tail)) = rest.split_first_chunk::<BLOCK>() {
+        // Sorted blocks cannot have smaller elements after this one.
+        if block[BLOCK - 1] >= id {
+            return count + simd::lt_mask(block, id).count_ones() as usize;
+        }
+        count += BLOCK;
+        rest = tail;
+    }
+    count + rest.iter().take_while(|&&x| x < id).count()
+}
+
+/// Returns `true` if every element of `sub` is contained in `sup`.
+///
+/// Both slices must be sorted in ascending order and must not contain duplicates,
+/// otherwise result is unspecified.
+#[must_use]
+pub fn is_subset_sorted(sub: &[TypeId], sup: &[TypeId]) -> bool {
+    let mut rest = sup;
+    for &id in sub {
+        let skipped = count_less_sorted(rest, id);
+        match rest.get(skipped) {
+            Some(&x) if x == id => rest = &rest[skipped + 1..],
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Writes elements contained in both `a` and `b` into start of `out` in ascending order
+/// and returns written part of `out`.
+///
+/// Both slices must be sorted in ascending order and must not contain duplicates,
+/// otherwise result is unspecified.
+///
+/// # Panics
+///
+/// Panics if `out` is shorter than the shortest of `a` and `b`.
+pub fn intersect_sorted<'o>(a: &[TypeId], b: &[TypeId], out: &'o mut [TypeId]) -> &'o mut [TypeId] {
+    let (small, mut rest) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    assert!(
+        out.len() >= small.len(),
+        "Output is shorter than the shortest input"
+    );
+    let mut len = 0;
+    // Skipping blocks helps only if most elements of longer slice are not in intersection.
+    if rest.len() < small.len() * BLOCK / 2 {
+        let mut i = 0;
+        while i < small.len() && !rest.is_empty() {
+            match small[i].cmp(&rest[0]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => rest = &rest[1..],
+                Ordering::Equal => {
+                    out[len] = small[i];
+                    len += 1;
+                    i += 1;
+                    rest = &rest[1..];
+                }
+            }
+        }
+        return &mut out[..len];
+    }
+    for &id in small {
+        let skipped = count_less_sorted(rest, id);
+        rest = &rest[skipped..];
+        match rest.first() {
+            Some(&x) if x == id => {
+                out[len] = id;
+                len += 1;
+                rest = &rest[1..];
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+    &mut out[..len]
+}
+
+/// Scalar implementations of block comparisons.
+#[cfg_attr(
+    any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "sse2"
+        ),
+        all(target_arch = "aarch64", target_feature = "neon")
+    ),
+    allow(dead_code)
+)]
+mod scalar {
+    use super::{BLOCK, TypeId};
+
+    /// Returns mask with bit `i` set if `block[i] == id`.
+    #[inline]
+    pub(super) fn eq_mask(block: &[TypeId; BLOCK], id: TypeId) -> u32 {
+        let mut mask = 0;
+        for (i, &x) in block.iter().enumerate() {
+            mask |= u32::from(x == id) << i;
+        }
+        mask
+    }
+
+    /// Returns mask with bit `i` set if `block[i] < id`.
+    #[inline]
+    pub(super) fn lt_mask(block: &[TypeId; BLOCK], id: TypeId) -> u32 {
+        let mut mask = 0;
+        for (i, &x) in block.iter().enumerate() {
+            mask |= u32::from(x < id) << i;
+        }
+        mask
+    }
+}
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "avx2"
+))]
+mod simd {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::{
+        __m256i, _mm256_castsi256_ps, _mm256_cmpeq_epi32, _mm256_cmpgt_epi32, _mm256_loadu_si256,
+        _mm256_movemask_ps, _mm256_set1_epi32,
+    };
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::{
+        __m256i, _mm256_castsi256_ps, _mm256_cmpeq_epi32, _mm256_cmpgt_epi32, _mm256_loadu_si256,
+        _mm256_movemask_ps, _mm256_set1_epi32,
+    };
+
+    use super::{BLOCK, TypeId};
+
+    #[inline]
+    pub(super) fn eq_mask(block: &[TypeId; BLOCK], id: TypeId) -> u32 {
+        // SAFETY: AVX2 is enabled at compile time and `TypeId` has layout of `u32`
+        // so block can be read as 256 bit vector using unaligned load.
+        #[allow(
+            clippy::cast_possible_wrap,
+            clippy::cast_sign_loss,
+            clippy::cast_ptr_alignment
+        )]
+        unsafe {
+            let values = _mm256_loadu_si256(block.as_ptr().cast::<__m256i>());
+            let eq = _mm256_cmpeq_epi32(values, _mm256_set1_epi32(id.as_u32() as i32));
+            _mm256_movemask_ps(_mm256_castsi256_ps(eq)) as u32
+        }
+    }
+
+    #[inline]
+    pub(super) fn lt_mask(block: &[TypeId; BLOCK], id: TypeId) -> u32 {
+        // SAFETY: Same as in `eq_mask`. Most significant bit of `TypeId` is always zero
+        // so signed comparison gives same result as unsigned one.
+        #[allow(
+            clippy::cast_possible_wrap,
+            clippy::cast_sign_loss,
+            clippy::cast_ptr_alignment
+        )]
+        unsafe {
+            let values = _mm256_loadu_si256(block.as_ptr().cast::<__m256i>());
+            let lt = _mm256_cmpgt_epi32(_mm256_set1_epi32(id.as_u32() as i32), values);
+            _mm256_movemask_ps(_mm256_castsi256_ps(lt)) as u32
+        }
+    }
+}
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2",
+    not(target_feature = "avx2")
+))]
+mod simd {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::{
+        __m128i, _mm_castsi128_ps, _mm_cmpeq_epi32, _mm_cmpgt_epi32, _mm_loadu_si128,
+        _mm_movemask_ps, _mm_set1_epi32,
+    };
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::{
+        __m128i, _mm_castsi128_ps, _mm_cmpeq_epi32, _mm_cmpgt_epi32, _mm_loadu_si128,
+        _mm_movemask_ps, _mm_set1_epi32,
+    };
+
+    use super::{BLOCK, TypeId};
+
+    #[inline]
+    pub(super) fn eq_mask(block: &[TypeId; BLOCK], id: TypeId) -> u32 {
+        // SAFETY: SSE2 is enabled at compile time and `TypeId` has layout of `u32`
+        // so both halves of block can be read as 128 bit vectors using unaligned loads.
+        #[allow(
+            clippy::cast_possible_wrap,
+            clippy::cast_sign_loss,
+            clippy::cast_ptr_alignment
+        )]
+        unsafe {
+            let ptr = block.as_ptr().cast::<__m128i>();
+            let needle = _mm_set1_epi32(id.as_u32() as i32);
+            let lo = _mm_cmpeq_epi32(_mm_loadu_si128(ptr), needle);
+            let hi = _mm_cmpeq_epi32(_mm_loadu_si128(ptr.add(1)), needle);
+            let lo = _mm_movemask_ps(_mm_castsi128_ps(lo)) as u32;
+            let hi = _mm_movemask_ps(_mm_castsi128_ps(hi)) as u32;
+            lo | (hi << 4)
+        }
+    }
+
+    #[inline]
+    pub(super) fn lt_mask(block: &[TypeId; BLOCK], id: TypeId) -> u32 {
+        // SAFETY: Same as in `eq_mask`. Most significant bit of `TypeId` is always zero
+        // so signed comparison gives same result as unsigned one.
+        #[allow(
+            clippy::cast_possible_wrap,
+            clippy::cast_sign_loss,
+            clippy::cast_ptr_alignment
+        )]
+        unsafe {
+            let ptr = block.as_ptr().cast::<__m128i>();
+            let needle = _mm_set1_epi32(id.as_u32() as i32);
+            let lo = _mm_cmpgt_epi32(needle, _mm_loadu_si128(ptr));
+            let hi = _mm_cmpgt_epi32(needle, _mm_loadu_si128(ptr.add(1)));
+            let lo = _mm_movemask_ps(_mm_castsi128_ps(lo)) as u32;
+            let hi = _mm_movemask_ps(_mm_castsi128_ps(hi)) as u32;
+            lo | (hi << 4)
+        }
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod simd {
+    use core::arch::aarch64::{
+        uint32x4_t, vaddvq_u32, vandq_u32, vceqq_u32, vcltq_u32, vdupq_n_u32, vld1q_u32,
+    };
+
+    use super::{BLOCK, TypeId};
+
+    /// Converts lanes with all bits set into mask.
+    #[inline]
+    fn to_mask(lo: uint32x4_t, hi: uint32x4_t) -> u32 {
+        const BITS: [u32; 4] = [1, 2, 4, 8];
+        // SAFETY: NEON is enabled at compile time.
+        unsafe {
+            let bits = vld1q_u32(BITS.as_ptr());
+            vaddvq_u32(vandq_u32(lo, bits)) | (vaddvq_u32(vandq_u32(hi, bits)) << 4)
+        }
+    }
+
+    #[inline]
+    pub(super) fn eq_mask(block: &[TypeId; BLOCK], id: TypeId) -> u32 {
+        // SAFETY: NEON is enabled at compile time and `TypeId` has layout of `u32`
+        // so both halves of block can be read as vectors of 4 `u32`s.
+        let (lo, hi) = unsafe {
+            let ptr = block.as_ptr().cast::<u32>();
+            let needle = vdupq_n_u32(id.as_u32());
+            (
+                vceqq_u32(vld1q_u32(ptr), needle),
+                vceqq_u32(vld1q_u32(ptr.add(4)), needle),
+            )
+        };
+        to_mask(lo, hi)
+    }
+
+    #[inline]
+    pub(super) fn lt_mask(block: &[TypeId; BLOCK], id: TypeId) -> u32 {
+        // SAFETY: Same as in `eq_mask`.
+        let (lo, hi) = unsafe {
+            let ptr = block.as_ptr().cast::<u32>();
+            let needle = vdupq_n_u32(id.as_u32());
+            (
+                vcltq_u32(vld1q_u32(ptr), needle),
+                vcltq_u32(vld1q_u32(ptr.add(4)), needle),
+            )
+        };
+        to_mask(lo, hi)
+    }
+}
+
+#[cfg(not(any(
+    all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    ),
+    all(target_arch = "aarch64", target_feature = "neon")
+)))]
+use scalar as simd;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(val: u32) -> TypeId {
+        TypeId::from_u32(val).unwrap()
+    }
+
+    fn ids(vals: impl IntoIterator<Item = u32>) -> Vec<TypeId> {
+        vals.into_iter().map(id).collect()
+    }
+
+    fn naive_intersection(a: &[TypeId], b: &[TypeId]) -> Vec<TypeId> {
+        a.iter().copied().filter(|x| b.contains(x)).collect()
+    }
+
+    #[test]
+    fn masks_match_scalar() {
+        // Values around boundaries of signed and unsigned comparison.
+        let vals = [1, 2, 3, 0x7FFF_FFFE, 0x7FFF_FFFF, 0x4000_0000, 0x3FFF_FFFF];
+        for &needle in &vals {
+            // Every combination of lanes that are less, equal and greater than needle.
+            for code in 0..3_u32.pow(8) {
+                let mut block = [id(needle); BLOCK];
+                let mut c = code;
+                for x in &mut block {
+                    *x = match (c % 3, needle) {
+                        (0, 1) | (2, 0x7FFF_FFFF) => id(needle),
+                        (0, n) => id(n - 1),
+                        (1, n) => id(n),
+                        (_, n) => id(n + 1),
+                    };
+                    c /= 3;
+                }
+                assert_eq!(
+                    simd::eq_mask(&block, id(needle)),
+                    scalar::eq_mask(&block, id(needle))
+                );
+                assert_eq!(
+                    simd::lt_mask(&block, id(needle)),
+                    scalar::lt_mask(&block, id(needle))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn search() {
+        for len in 0..40 {
+            let hay = ids((0..len).map(|x| x * 3 + 5));
+            for needle in 1..len * 3 + 10 {
+                let needle = id(needle);
+                let expected = hay.iter().position(|&x| x == needle);
+                assert_eq!(position(&hay, needle), expected);
+                assert_eq!(contains(&hay, needle), expected.is_some());
+            }
+        }
+        let with_duplicates = ids([4, 7, 7, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(position(&with_duplicates, id(7)), Some(1));
+        assert_eq!(position(&with_duplicates, id(9)), Some(11));
+    }
+
+    fn subsets(universe: &[TypeId], step: usize) -> Vec<Vec<TypeId>> {
+        (0..1_u64 << universe.len())
+            .step_by(step)
+            .chain([(1 << universe.len()) - 1])
+            .map(|bits| {
+                universe
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| bits & (1 << i) != 0)
+                    .map(|(_, &x)| x)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn check_sorted_sets(sets: &[Vec<TypeId>]) {
+        let mut out = vec![id(1); 64];
+        for a in sets {
+            for b in sets {
+                let expected = naive_intersection(a, b);
+                assert_eq!(is_subset_sorted(a, b), expected.len() == a.len());
+                assert_eq!(intersect_sorted(a, b, &mut out), expected.as_slice());
+            }
+        }
+    }
+
+    #[test]
+    fn sorted_sets() {
+        // Every pair of subsets of small universe.
+        check_sorted_sets(&subsets(&ids(1..=9), 1));
+        // Sets that span several blocks.
+        let mut sets = subsets(&ids((1..=40).map(|x| x * 2)), 0x3_7FFF_FFFF);
+        sets.push(ids([1, 81]));
+        sets.push(ids((1..=40).map(|x| x * 2 + 1)));
+        check_sorted_sets(&sets);
+    }
+
+    #[test]
+    #[should_panic(expected = "Output is shorter than the shortest input")]
+    fn short_output() {
+        let _ = intersect_sorted(&ids([1, 2]), &ids([1, 2, 3]), &mut [id(1)]);
+    }
+}
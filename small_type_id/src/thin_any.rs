@@ -0,0 +1,248 @@
+//! Implementation of [`ThinAny`] and [`ThinAnySend`].
+
+use alloc::boxed::Box;
+use core::ptr::NonNull;
+
+use crate::{HasTypeId, TypeId};
+
+/// Owning pointer to value of any type that implements [`HasTypeId`].
+///
+/// Unlike `Box<dyn SmallAny>`, which is a pointer and a pointer to vtable,
+/// it is a single pointer (`Option<ThinAny>` too).
+/// Allocation starts from [`TypeId`] of value and pointer to function that drops it,
+/// so checking type of value reads only that header.
+///
+/// Available only if feature [`alloc`](crate#feature-alloc) is enabled.
+///
+/// ```
+/// use small_type_id::HasTypeId as _;
+/// use small_type_id::any::ThinAny;
+///
+/// #[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+/// struct Ping(u64);
+/// #[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+/// struct Text(String);
+///
+/// let queue = vec![ThinAny::new(Ping(1)), ThinAny::new(Text("hi".to_owned()))];
+/// assert_eq!(size_of_val(&queue[0]), size_of::<usize>());
+/// assert_eq!(queue[1].type_id(), Text::TYPE_ID);
+/// for msg in queue {
+///     match msg.downcast::<Ping>() {
+///         Ok(ping) => assert_eq!(ping, Ping(1)),
+///         Err(msg) => assert!(msg.is::<Text>()),
+///     }
+/// }
+/// ```
+pub struct ThinAny {
+    ptr: NonNull<Header>,
+}
+
+/// [`ThinAny`] that holds only values that implement [`Send`] so it can be sent between threads.
+///
+/// Available only if feature [`alloc`](crate#feature-alloc) is enabled.
+///
+/// ```
+/// use small_type_id::any::ThinAnySend;
+///
+/// #[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+/// struct Ping(u64);
+///
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// std::thread::spawn(move || tx.send(ThinAnySend::new(Ping(1))).unwrap());
+/// assert_eq!(rx.recv().unwrap().downcast::<Ping>().unwrap(), Ping(1));
+/// ```
+///
+/// ```compile_fail
+/// use std::rc::Rc;
+///
+/// use small_type_id::any::ThinAnySend;
+///
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Shared(Rc<u64>);
+///
+/// let _ = ThinAnySend::new(Shared(Rc::new(1)));
+/// ```
+///
+/// It is not [`Sync`] because stored values are not required to be [`Sync`].
+pub struct ThinAnySend(ThinAny);
+
+// SAFETY: `ThinAnySend` is created only from values that implement `Send`
+// and it never gives out inner `ThinAny` by mutable reference,
+// so it cannot be swapped with one that holds value that is not `Send`.
+// Dropping value on other thread is fine because it is `Send`.
+unsafe impl Send for ThinAnySend {}
+
+#[repr(C)]
+struct Header {
+    type_id: TypeId,
+    // Drops value and frees allocation.
+    drop: unsafe fn(NonNull<Header>),
+}
+
+// Header is at the start so pointer to it is also pointer to whole allocation.
+#[repr(C)]
+struct Repr<T> {
+    header: Header,
+    value: T,
+}
+
+/// # Safety
+///
+/// `ptr` must be created by `ThinAny::new::<T>` and must not be used after call.
+unsafe fn drop_repr<T>(ptr: NonNull<Header>) {
+    // SAFETY: Caller ensures that allocation was created from `Box<Repr<T>>`.
+    drop(unsafe { Box::from_raw(ptr.cast::<Repr<T>>().as_ptr()) });
+}
+
+impl ThinAny {
+    /// Moves value to heap.
+    #[must_use]
+    pub fn new<T: HasTypeId>(val: T) -> Self {
+        let repr = Box::new(Repr {
+            header: Header {
+                type_id: T::TYPE_ID,
+                drop: drop_repr::<T>,
+            },
+            value: val,
+        });
+        let ptr = NonNull::from(Box::leak(repr)).cast::<Header>();
+        Self { ptr }
+    }
+
+    fn header(&self) -> &Header {
+        // SAFETY: Pointer is valid while `self` is alive.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Returns [`TypeId`] of stored value.
+    #[must_use]
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.header().type_id
+    }
+
+    /// Returns `true` if stored value has type `T`.
+    ///
+    /// Calls [`ensure_verified`](crate::ensure_verified) first
+    /// because downcasts rely on uniqueness of [`TypeId`]s.
+    #[must_use]
+    #[inline]
+    pub fn is<T: HasTypeId>(&self) -> bool {
+        crate::implementation::ensure_verified();
+        self.type_id() == T::TYPE_ID
+    }
+
+    /// Returns reference to stored value if it has type `T`.
+    #[must_use]
+    pub fn downcast_ref<T: HasTypeId>(&self) -> Option<&T> {
+        if !self.is::<T>() {
+            return None;
+        }
+        let repr = self.ptr.cast::<Repr<T>>();
+        // SAFETY: Allocation was created by `ThinAny::new::<T>`
+        // because `TypeId`s are unique by contract of `HasTypeId`.
+        Some(unsafe { &repr.as_ref().value })
+    }
+
+    /// Returns mutable reference to stored value if it has type `T`.
+    #[must_use]
+    pub fn downcast_mut<T: HasTypeId>(&mut self) -> Option<&mut T> {
+        if !self.is::<T>() {
+            return None;
+        }
+        let mut repr = self.ptr.cast::<Repr<T>>();
+        // SAFETY: Same as in `downcast_ref`.
+        Some(unsafe { &mut repr.as_mut().value })
+    }
+
+    /// Moves stored value out of heap if it has type `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` back if value has different type.
+    pub fn downcast<T: HasTypeId>(self) -> Result<T, Self> {
+        if !self.is::<T>() {
+            return Err(self);
+        }
+        let repr = self.ptr.cast::<Repr<T>>();
+        core::mem::forget(self);
+        // SAFETY: Same as in `downcast_ref`, and we don't drop `self` to avoid double free.
+        let repr = unsafe { Box::from_raw(repr.as_ptr()) };
+        Ok(repr.value)
+    }
+}
+
+impl Drop for ThinAny {
+    fn drop(&mut self) {
+        let drop_fn = self.header().drop;
+        // SAFETY: Function matches type of value, and pointer is not used after drop.
+        unsafe { drop_fn(self.ptr) };
+    }
+}
+
+/// Prints [`TypeId`] of stored value.
+impl core::fmt::Debug for ThinAny {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ThinAny").field(&self.type_id()).finish()
+    }
+}
+
+impl ThinAnySend {
+    /// Moves value to heap.
+    #[must_use]
+    pub fn new<T: HasTypeId + Send>(val: T) -> Self {
+        Self(ThinAny::new(val))
+    }
+
+    /// Returns [`TypeId`] of stored value.
+    #[must_use]
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.0.type_id()
+    }
+
+    /// Returns `true` if stored value has type `T`.
+    ///
+    /// Calls [`ensure_verified`](crate::ensure_verified) first
+    /// because downcasts rely on uniqueness of [`TypeId`]s.
+    #[must_use]
+    #[inline]
+    pub fn is<T: HasTypeId>(&self) -> bool {
+        self.0.is::<T>()
+    }
+
+    /// Returns reference to stored value if it has type `T`.
+    #[must_use]
+    pub fn downcast_ref<T: HasTypeId>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+
+    /// Returns mutable reference to stored value if it has type `T`.
+    #[must_use]
+    pub fn downcast_mut<T: HasTypeId>(&mut self) -> Option<&mut T> {
+        self.0.downcast_mut()
+    }
+
+    /// Moves stored value out of heap if it has type `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` back if value has different type.
+    pub fn downcast<T: HasTypeId>(self) -> Result<T, Self> {
+        self.0.downcast().map_err(Self)
+    }
+}
+
+/// Forgets that stored value is [`Send`].
+impl From<ThinAnySend> for ThinAny {
+    fn from(val: ThinAnySend) -> Self {
+        val.0
+    }
+}
+
+/// Prints [`TypeId`] of stored value.
+impl core::fmt::Debug for ThinAnySend {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ThinAnySend").field(&self.type_id()).finish()
+    }
+}
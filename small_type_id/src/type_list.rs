@@ -0,0 +1,152 @@
+//! Lists of types known at compile time.
+//!
+//! [`TypeList`] is a type that lists up to 16 types, usually declared using
+//! [`define_type_list!`](crate::define_type_list) macro. It provides [`TypeId`]s of listed types
+//! and their positions in const context, e.g. to select discriminants of variant-like storage.
+//!
+//! ```
+//! use small_type_id::konst;
+//!
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Position;
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Velocity;
+//! #[derive(small_type_id::HasTypeId)]
+//! struct Health;
+//!
+//! small_type_id::define_type_list! {
+//!     type Physics = (Position, Velocity);
+//! }
+//!
+//! const { assert!(matches!(Physics::index_of::<Velocity>(), Some(1))) };
+//! assert_eq!(Physics::LEN, 2);
+//! assert!(!Physics::contains::<Health>());
+//!
+//! // Lists with same types in different order have same sorted ids.
+//! small_type_id::define_type_list! {
+//!     type Reordered = (Velocity, Position);
+//! }
+//! const { assert!(konst::eq(&Physics::SORTED, &Reordered::SORTED)) };
+//! const { assert!(!konst::eq(&Physics::IDS, &Reordered::IDS)) };
+//! ```
+//!
+//! Lists with duplicate [`TypeId`]s fail to compile at declaration, even if they are never used:
+//!
+//! ```compile_fail
+//! #[derive(small_type_id::HasTypeId)]
+//! struct A;
+//!
+//! small_type_id::define_type_list! {
+//!     type Twice = (A, A);
+//! }
+//! ```
+//!
+//! Lists written directly as [`TypeList`] types fail to compile only when their ids are evaluated,
+//! e.g. by [`TypeList::new`] or [`TypeList::index_of`].
+
+use core::marker::PhantomData;
+
+use crate::{HasTypeId, TypeId, konst};
+
+/// List of `N` types listed in tuple `T`.
+///
+/// Use [`define_type_list!`](crate::define_type_list) macro to declare this type.
+/// See [module documentation](self) for example.
+pub struct TypeList<T, const N: usize>(PhantomData<fn() -> T>);
+
+/// Tuple of `N` types that implement [`HasTypeId`].
+///
+/// Implemented for tuples with up to 16 elements.
+pub trait TypeTuple<const N: usize> {
+    /// [`TypeId`]s of elements in order.
+    const IDS: [TypeId; N];
+}
+
+macro_rules! impl_type_tuple {
+    ($n:literal: $($t:ident),*) => {
+        impl<$($t: HasTypeId),*> TypeTuple<$n> for ($($t,)*) {
+            const IDS: [TypeId; $n] = [$($t::TYPE_ID),*];
+        }
+    };
+}
+
+impl_type_tuple!(0:);
+impl_type_tuple!(1: T0);
+impl_type_tuple!(2: T0, T1);
+impl_type_tuple!(3: T0, T1, T2);
+impl_type_tuple!(4: T0, T1, T2, T3);
+impl_type_tuple!(5: T0, T1, T2, T3, T4);
+impl_type_tuple!(6: T0, T1, T2, T3, T4, T5);
+impl_type_tuple!(7: T0, T1, T2, T3, T4, T5, T6);
+impl_type_tuple!(8: T0, T1, T2, T3, T4, T5, T6, T7);
+impl_type_tuple!(9: T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_type_tuple!(10: T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_type_tuple!(11: T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_type_tuple!(12: T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_type_tuple!(13: T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+impl_type_tuple!(14: T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13);
+impl_type_tuple!(15: T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14);
+impl_type_tuple!(16: T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15);
+
+impl<T: TypeTuple<N>, const N: usize> TypeList<T, N> {
+    /// Number of listed types.
+    pub const LEN: usize = N;
+
+    /// [`TypeId`]s of listed types in order.
+    ///
+    /// Evaluation fails to compile if some types have same [`TypeId`].
+    pub const IDS: [TypeId; N] = {
+        assert!(konst::is_unique(&T::IDS), "Duplicate TypeId in TypeList");
+        T::IDS
+    };
+
+    /// [`TypeId`]s of listed types sorted in ascending order.
+    ///
+    /// Same for lists of same types regardless of their order,
+    /// so it can be compared using [`konst::eq`].
+    pub const SORTED: [TypeId; N] = konst::sort(Self::IDS);
+
+    /// Creates value of list type.
+    /// Fails to compile if some types have same [`TypeId`].
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        let _ = Self::IDS;
+        Self(PhantomData)
+    }
+
+    /// Returns position of type `U` in list.
+    #[must_use]
+    #[inline]
+    pub const fn index_of<U: HasTypeId + ?Sized>() -> Option<usize> {
+        konst::index_of(&Self::IDS, U::TYPE_ID)
+    }
+
+    /// Returns `true` if type `U` is listed.
+    #[must_use]
+    #[inline]
+    pub const fn contains<U: HasTypeId + ?Sized>() -> bool {
+        Self::index_of::<U>().is_some()
+    }
+}
+
+impl<T, const N: usize> Clone for TypeList<T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const N: usize> Copy for TypeList<T, N> {}
+
+impl<T: TypeTuple<N>, const N: usize> Default for TypeList<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lists [`TypeId`]s of types.
+impl<T: TypeTuple<N>, const N: usize> core::fmt::Debug for TypeList<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(Self::IDS.iter()).finish()
+    }
+}
@@ -0,0 +1,669 @@
+//! Containers that store at most one value of every type.
+//!
+//! [`TypeMap`] owns its values and requires feature [`alloc`](crate#feature-alloc).
+//! [`HeaplessTypeMap`] has fixed capacity and stores values inline,
+//! so it can be used without allocator. It requires feature [`heapless`](crate#feature-heapless).
+//!
+//! Both use [`TypeId`] as key, which takes only 4 bytes.
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+#[cfg(feature = "heapless")]
+use core::mem::MaybeUninit;
+
+use crate::any::SmallAny;
+#[cfg(feature = "alloc")]
+use crate::any::downcast_box_unchecked;
+#[cfg(feature = "alloc")]
+use crate::hash::TypeIdMap;
+use crate::{HasTypeId, TypeId};
+
+/// Map from type to value of that type.
+///
+/// Available only if feature [`alloc`](crate#feature-alloc) is enabled.
+///
+/// ```
+/// use small_type_id::type_map::TypeMap;
+///
+/// #[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+/// struct Gravity(f32);
+/// #[derive(small_type_id::HasTypeId, Debug, PartialEq, Default)]
+/// struct FrameCount(u64);
+///
+/// let mut resources = TypeMap::new();
+/// resources.insert(Gravity(9.8));
+/// *resources.entry::<FrameCount>().or_default() = FrameCount(1);
+/// resources.get_mut::<FrameCount>().unwrap().0 += 1;
+///
+/// assert_eq!(resources.get::<Gravity>(), Some(&Gravity(9.8)));
+/// assert_eq!(resources.remove::<FrameCount>(), Some(FrameCount(2)));
+/// assert_eq!(resources.len(), 1);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+pub struct TypeMap {
+    // Every value is stored with key equal to its `TypeId`.
+    map: TypeIdMap<Box<dyn SmallAny>>,
+}
+
+/// View into a single entry of [`TypeMap`] for type `T`.
+///
+/// Returned by [`TypeMap::entry`].
+#[cfg(feature = "alloc")]
+pub enum Entry<'a, T: HasTypeId> {
+    /// Map contains value of type `T`.
+    Occupied(OccupiedEntry<'a, T>),
+    /// Map doesn't contain value of type `T`.
+    Vacant(VacantEntry<'a, T>),
+}
+
+/// View into entry of [`TypeMap`] that contains value of type `T`.
+#[cfg(feature = "alloc")]
+pub struct OccupiedEntry<'a, T: HasTypeId> {
+    inner: hashbrown::hash_map::OccupiedEntry<
+        'a,
+        TypeId,
+        Box<dyn SmallAny>,
+        crate::hash::BuildTypeIdHasher,
+    >,
+    _marker: PhantomData<T>,
+}
+
+/// View into entry of [`TypeMap`] that doesn't contain value of type `T`.
+#[cfg(feature = "alloc")]
+pub struct VacantEntry<'a, T: HasTypeId> {
+    inner: hashbrown::hash_map::VacantEntry<
+        'a,
+        TypeId,
+        Box<dyn SmallAny>,
+        crate::hash::BuildTypeIdHasher,
+    >,
+    _marker: PhantomData<T>,
+}
+
+/// Map from type to value of that type with capacity `N`.
+///
+/// Available only if feature [`heapless`](crate#feature-heapless) is enabled.
+/// Looks up values using linear search over [`TypeId`]s so it is intended for small `N`.
+///
+/// Every value is stored inline, in slot that has size and alignment of `S`.
+/// Inserting value that is larger or more aligned than `S` fails to compile.
+///
+/// ```
+/// use small_type_id::type_map::HeaplessTypeMap;
+///
+/// #[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+/// struct Gravity(f32);
+/// #[derive(small_type_id::HasTypeId, Debug, PartialEq, Default)]
+/// struct FrameCount(u64);
+///
+/// let mut resources = HeaplessTypeMap::<2>::new();
+/// resources.insert(Gravity(9.8)).unwrap();
+/// *resources.entry::<FrameCount>().or_default().unwrap() = FrameCount(1);
+/// resources.get_mut::<FrameCount>().unwrap().0 += 1;
+///
+/// assert_eq!(resources.get::<Gravity>(), Some(&Gravity(9.8)));
+/// assert_eq!(resources.remove::<FrameCount>(), Some(FrameCount(2)));
+/// assert_eq!(resources.len(), 1);
+/// ```
+///
+/// ```compile_fail
+/// use small_type_id::type_map::HeaplessTypeMap;
+///
+/// #[derive(small_type_id::HasTypeId)]
+/// struct Large([u64; 8]);
+///
+/// let mut resources = HeaplessTypeMap::<2, [u64; 4]>::new();
+/// let _ = resources.insert(Large([0; 8]));
+/// ```
+#[cfg(feature = "heapless")]
+pub struct HeaplessTypeMap<const N: usize, S = [usize; 4]> {
+    // Ids are stored separately to make search cache friendly.
+    // Value at every index has `TypeId` stored at the same index.
+    ids: heapless::Vec<TypeId, N>,
+    values: heapless::Vec<InlineValue<S>, N>,
+}
+
+/// View into a single entry of [`HeaplessTypeMap`] for type `T`.
+///
+/// Returned by [`HeaplessTypeMap::entry`].
+#[cfg(feature = "heapless")]
+pub enum HeaplessEntry<'a, T: HasTypeId, const N: usize, S> {
+    /// Map contains value of type `T`.
+    Occupied(HeaplessOccupiedEntry<'a, T, N, S>),
+    /// Map doesn't contain value of type `T`.
+    Vacant(HeaplessVacantEntry<'a, T, N, S>),
+}
+
+/// View into entry of [`HeaplessTypeMap`] that contains value of type `T`.
+#[cfg(feature = "heapless")]
+pub struct HeaplessOccupiedEntry<'a, T: HasTypeId, const N: usize, S> {
+    map: &'a mut HeaplessTypeMap<N, S>,
+    // Index of value of type `T`.
+    idx: usize,
+    _marker: PhantomData<T>,
+}
+
+/// View into entry of [`HeaplessTypeMap`] that doesn't contain value of type `T`.
+#[cfg(feature = "heapless")]
+pub struct HeaplessVacantEntry<'a, T: HasTypeId, const N: usize, S> {
+    map: &'a mut HeaplessTypeMap<N, S>,
+    _marker: PhantomData<T>,
+}
+
+/// Value of any type that fits into `S`, stored inline.
+#[cfg(feature = "heapless")]
+struct InlineValue<S> {
+    // Contains initialized value of type for which functions below are instantiated.
+    storage: MaybeUninit<S>,
+    drop: unsafe fn(*mut u8),
+    as_dyn: fn(*const u8) -> *const dyn SmallAny,
+    // Stored value may be neither `Send` nor `Sync`.
+    _marker: PhantomData<*mut ()>,
+}
+
+#[cfg(feature = "alloc")]
+impl TypeMap {
+    /// Creates empty map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates empty map that can hold at least `capacity` values without reallocation.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: TypeIdMap::with_capacity_and_hasher(capacity, crate::hash::BuildTypeIdHasher),
+        }
+    }
+
+    /// Returns number of stored values.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if map contains no values.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Removes all values.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Inserts value and returns previous value of type `T`, if any.
+    pub fn insert<T: HasTypeId>(&mut self, val: T) -> Option<T> {
+        // Values are found by their `TypeId`s so they must be unique
+        // before first value is stored.
+        crate::implementation::ensure_verified();
+        let old = self.map.insert(T::TYPE_ID, Box::new(val))?;
+        // SAFETY: Values are stored with key equal to their `TypeId`.
+        Some(*unsafe { downcast_box_unchecked(old) })
+    }
+
+    /// Returns `true` if map contains value of type `T`.
+    #[must_use]
+    #[inline]
+    pub fn contains<T: HasTypeId>(&self) -> bool {
+        self.map.contains_key(&T::TYPE_ID)
+    }
+
+    /// Returns reference to value of type `T`.
+    #[must_use]
+    pub fn get<T: HasTypeId>(&self) -> Option<&T> {
+        let val = self.map.get(&T::TYPE_ID)?;
+        // SAFETY: Values are stored with key equal to their `TypeId`.
+        Some(unsafe { val.downcast_ref_unchecked() })
+    }
+
+    /// Returns mutable reference to value of type `T`.
+    #[must_use]
+    pub fn get_mut<T: HasTypeId>(&mut self) -> Option<&mut T> {
+        let val = self.map.get_mut(&T::TYPE_ID)?;
+        // SAFETY: Values are stored with key equal to their `TypeId`.
+        Some(unsafe { val.downcast_mut_unchecked() })
+    }
+
+    /// Removes value of type `T` from map and returns it.
+    pub fn remove<T: HasTypeId>(&mut self) -> Option<T> {
+        let old = self.map.remove(&T::TYPE_ID)?;
+        // SAFETY: Values are stored with key equal to their `TypeId`.
+        Some(*unsafe { downcast_box_unchecked(old) })
+    }
+
+    /// Returns entry for type `T` for in-place manipulation.
+    pub fn entry<T: HasTypeId>(&mut self) -> Entry<'_, T> {
+        // Same as in `insert`, vacant entry allows to store value.
+        crate::implementation::ensure_verified();
+        match self.map.entry(T::TYPE_ID) {
+            hashbrown::hash_map::Entry::Occupied(inner) => Entry::Occupied(OccupiedEntry {
+                inner,
+                _marker: PhantomData,
+            }),
+            hashbrown::hash_map::Entry::Vacant(inner) => Entry::Vacant(VacantEntry {
+                inner,
+                _marker: PhantomData,
+            }),
+        }
+    }
+
+    /// Iterates over stored values in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (TypeId, &dyn SmallAny)> {
+        self.map.iter().map(|(&k, v)| (k, &**v))
+    }
+
+    /// Iterates over mutable references to stored values in arbitrary order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (TypeId, &mut dyn SmallAny)> {
+        self.map.iter_mut().map(|(&k, v)| (k, &mut **v))
+    }
+}
+
+/// Lists [`TypeId`]s of stored values.
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for TypeMap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.map.keys()).finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: HasTypeId> Entry<'a, T> {
+    /// Inserts `default` if entry is vacant and returns reference to value.
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts result of `default` if entry is vacant and returns reference to value.
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        match self {
+            Self::Occupied(e) => e.into_mut(),
+            Self::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    /// Inserts default value if entry is vacant and returns reference to value.
+    pub fn or_default(self) -> &'a mut T
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
+    }
+
+    /// Calls `f` with stored value if entry is occupied.
+    #[must_use]
+    pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+        if let Self::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: HasTypeId> OccupiedEntry<'a, T> {
+    /// Returns reference to stored value.
+    #[must_use]
+    pub fn get(&self) -> &T {
+        // SAFETY: Entry is created only for key `T::TYPE_ID` so value has type `T`.
+        unsafe { self.inner.get().downcast_ref_unchecked() }
+    }
+
+    /// Returns mutable reference to stored value.
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut T {
+        // SAFETY: Same as in `get`.
+        unsafe { self.inner.get_mut().downcast_mut_unchecked() }
+    }
+
+    /// Converts entry into mutable reference to stored value.
+    #[must_use]
+    pub fn into_mut(self) -> &'a mut T {
+        // SAFETY: Same as in `get`.
+        unsafe { self.inner.into_mut().downcast_mut_unchecked() }
+    }
+
+    /// Replaces stored value and returns previous one.
+    pub fn insert(&mut self, val: T) -> T {
+        core::mem::replace(self.get_mut(), val)
+    }
+
+    /// Removes stored value from map and returns it.
+    #[must_use]
+    pub fn remove(self) -> T {
+        // SAFETY: Same as in `get`.
+        *unsafe { downcast_box_unchecked(self.inner.remove()) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: HasTypeId> VacantEntry<'a, T> {
+    /// Inserts value into map and returns reference to it.
+    pub fn insert(self, val: T) -> &'a mut T {
+        let val = self.inner.insert(Box::new(val));
+        // SAFETY: We just inserted value of type `T`.
+        unsafe { val.downcast_mut_unchecked() }
+    }
+}
+
+/// # Safety
+///
+/// `ptr` must point to valid value of type `T` which is not used after call.
+#[cfg(feature = "heapless")]
+unsafe fn drop_value<T>(ptr: *mut u8) {
+    // SAFETY: Ensured by caller.
+    unsafe { ptr.cast::<T>().drop_in_place() }
+}
+
+#[cfg(feature = "heapless")]
+fn as_dyn<T: HasTypeId>(ptr: *const u8) -> *const dyn SmallAny {
+    ptr.cast::<T>()
+}
+
+#[cfg(feature = "heapless")]
+impl<S> InlineValue<S> {
+    fn new<T: HasTypeId>(val: T) -> Self {
+        const {
+            assert!(
+                size_of::<T>() <= size_of::<S>() && align_of::<T>() <= align_of::<S>(),
+                "Value must fit into slot of HeaplessTypeMap"
+            );
+        };
+        let mut storage = MaybeUninit::<S>::uninit();
+        // SAFETY: Storage is large and aligned enough for `T`.
+        unsafe { storage.as_mut_ptr().cast::<T>().write(val) };
+        Self {
+            storage,
+            drop: drop_value::<T>,
+            as_dyn: as_dyn::<T>,
+            _marker: PhantomData,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// Stored value must have type `T`.
+    unsafe fn get<T>(&self) -> &T {
+        // SAFETY: Ensured by caller.
+        unsafe { &*self.storage.as_ptr().cast::<T>() }
+    }
+
+    /// # Safety
+    ///
+    /// Stored value must have type `T`.
+    unsafe fn get_mut<T>(&mut self) -> &mut T {
+        // SAFETY: Ensured by caller.
+        unsafe { &mut *self.storage.as_mut_ptr().cast::<T>() }
+    }
+
+    /// # Safety
+    ///
+    /// Stored value must have type `T`.
+    unsafe fn into_inner<T>(self) -> T {
+        let this = core::mem::ManuallyDrop::new(self);
+        // SAFETY: Ensured by caller. Value is not dropped by `this`.
+        unsafe { this.storage.as_ptr().cast::<T>().read() }
+    }
+
+    fn as_dyn(&self) -> &dyn SmallAny {
+        // SAFETY: Storage contains value for which `as_dyn` is instantiated.
+        unsafe { &*(self.as_dyn)(self.storage.as_ptr().cast()) }
+    }
+
+    fn as_dyn_mut(&mut self) -> &mut dyn SmallAny {
+        let ptr = (self.as_dyn)(self.storage.as_mut_ptr().cast_const().cast());
+        // SAFETY: Same as in `as_dyn`, and pointer is derived from mutable reference.
+        unsafe { &mut *ptr.cast_mut() }
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<S> Drop for InlineValue<S> {
+    fn drop(&mut self) {
+        // SAFETY: Storage contains value for which `drop` is instantiated.
+        unsafe { (self.drop)(self.storage.as_mut_ptr().cast()) }
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize, S> HeaplessTypeMap<N, S> {
+    /// Creates empty map.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            ids: heapless::Vec::new(),
+            values: heapless::Vec::new(),
+        }
+    }
+
+    /// Returns number of stored values.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if map contains no values.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns maximum number of values that map can hold.
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Removes all values.
+    pub fn clear(&mut self) {
+        self.ids.clear();
+        self.values.clear();
+    }
+
+    fn position(&self, type_id: TypeId) -> Option<usize> {
+        self.ids.iter().position(|&x| x == type_id)
+    }
+
+    /// Inserts value and returns previous value of type `T`, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns `val` back if map is full.
+    pub fn insert<T: HasTypeId>(&mut self, val: T) -> Result<Option<T>, T> {
+        match self.entry::<T>() {
+            HeaplessEntry::Occupied(mut e) => Ok(Some(e.insert(val))),
+            HeaplessEntry::Vacant(e) => e.insert(val).map(|_| None),
+        }
+    }
+
+    /// Returns `true` if map contains value of type `T`.
+    #[must_use]
+    #[inline]
+    pub fn contains<T: HasTypeId>(&self) -> bool {
+        self.position(T::TYPE_ID).is_some()
+    }
+
+    /// Returns reference to value of type `T`.
+    #[must_use]
+    pub fn get<T: HasTypeId>(&self) -> Option<&T> {
+        let idx = self.position(T::TYPE_ID)?;
+        // SAFETY: Values are stored at index of their `TypeId`.
+        Some(unsafe { self.values[idx].get() })
+    }
+
+    /// Returns mutable reference to value of type `T`.
+    #[must_use]
+    pub fn get_mut<T: HasTypeId>(&mut self) -> Option<&mut T> {
+        let idx = self.position(T::TYPE_ID)?;
+        // SAFETY: Values are stored at index of their `TypeId`.
+        Some(unsafe { self.values[idx].get_mut() })
+    }
+
+    /// Removes value of type `T` from map and returns it.
+    pub fn remove<T: HasTypeId>(&mut self) -> Option<T> {
+        let idx = self.position(T::TYPE_ID)?;
+        self.ids.swap_remove(idx);
+        // SAFETY: Values are stored at index of their `TypeId`.
+        Some(unsafe { self.values.swap_remove(idx).into_inner() })
+    }
+
+    /// Returns entry for type `T` for in-place manipulation.
+    pub fn entry<T: HasTypeId>(&mut self) -> HeaplessEntry<'_, T, N, S> {
+        // Values are found by their `TypeId`s so they must be unique
+        // before first value is stored.
+        crate::implementation::ensure_verified();
+        match self.position(T::TYPE_ID) {
+            Some(idx) => HeaplessEntry::Occupied(HeaplessOccupiedEntry {
+                map: self,
+                idx,
+                _marker: PhantomData,
+            }),
+            None => HeaplessEntry::Vacant(HeaplessVacantEntry {
+                map: self,
+                _marker: PhantomData,
+            }),
+        }
+    }
+
+    /// Iterates over stored values in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (TypeId, &dyn SmallAny)> {
+        self.ids
+            .iter()
+            .copied()
+            .zip(self.values.iter().map(InlineValue::as_dyn))
+    }
+
+    /// Iterates over mutable references to stored values in arbitrary order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (TypeId, &mut dyn SmallAny)> {
+        self.ids
+            .iter()
+            .copied()
+            .zip(self.values.iter_mut().map(InlineValue::as_dyn_mut))
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize, S> Default for HeaplessTypeMap<N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lists [`TypeId`]s of stored values.
+#[cfg(feature = "heapless")]
+impl<const N: usize, S> core::fmt::Debug for HeaplessTypeMap<N, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.ids.iter()).finish()
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<'a, T: HasTypeId, const N: usize, S> HeaplessEntry<'a, T, N, S> {
+    /// Inserts `default` if entry is vacant and returns reference to value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `default` back if entry is vacant and map is full.
+    pub fn or_insert(self, default: T) -> Result<&'a mut T, T> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts result of `default` if entry is vacant and returns reference to value.
+    ///
+    /// # Errors
+    ///
+    /// Returns result of `default` back if entry is vacant and map is full.
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> Result<&'a mut T, T> {
+        match self {
+            Self::Occupied(e) => Ok(e.into_mut()),
+            Self::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    /// Inserts default value if entry is vacant and returns reference to value.
+    ///
+    /// # Errors
+    ///
+    /// Returns default value back if entry is vacant and map is full.
+    pub fn or_default(self) -> Result<&'a mut T, T>
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
+    }
+
+    /// Calls `f` with stored value if entry is occupied.
+    #[must_use]
+    pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+        if let Self::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<'a, T: HasTypeId, const N: usize, S> HeaplessOccupiedEntry<'a, T, N, S> {
+    /// Returns reference to stored value.
+    #[must_use]
+    pub fn get(&self) -> &T {
+        // SAFETY: Entry is created only for index of `T::TYPE_ID` so value has type `T`.
+        unsafe { self.map.values[self.idx].get() }
+    }
+
+    /// Returns mutable reference to stored value.
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut T {
+        // SAFETY: Same as in `get`.
+        unsafe { self.map.values[self.idx].get_mut() }
+    }
+
+    /// Converts entry into mutable reference to stored value.
+    #[must_use]
+    pub fn into_mut(self) -> &'a mut T {
+        // SAFETY: Same as in `get`.
+        unsafe { self.map.values[self.idx].get_mut() }
+    }
+
+    /// Replaces stored value and returns previous one.
+    pub fn insert(&mut self, val: T) -> T {
+        core::mem::replace(self.get_mut(), val)
+    }
+
+    /// Removes stored value from map and returns it.
+    #[must_use]
+    pub fn remove(self) -> T {
+        self.map.ids.swap_remove(self.idx);
+        // SAFETY: Same as in `get`.
+        unsafe { self.map.values.swap_remove(self.idx).into_inner() }
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<'a, T: HasTypeId, const N: usize, S> HeaplessVacantEntry<'a, T, N, S> {
+    /// Inserts value into map and returns reference to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `val` back if map is full.
+    pub fn insert(self, val: T) -> Result<&'a mut T, T> {
+        if self.map.ids.is_full() {
+            return Err(val);
+        }
+        // Both have same length so both have free space.
+        let _ = self.map.ids.push(T::TYPE_ID);
+        let _ = self.map.values.push(InlineValue::new(val));
+        let last = self.map.values.len() - 1;
+        // SAFETY: We just inserted value of type `T`.
+        Ok(unsafe { self.map.values[last].get_mut() })
+    }
+}
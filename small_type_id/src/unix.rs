@@ -1,9 +1,13 @@
+#[cfg(feature = "json_report")]
+use core::ffi::{CStr, c_char};
 use core::ffi::{c_int, c_void};
 
 const STDERR_FILENO: c_int = 2;
 
 unsafe extern "C" {
     fn write(fd: c_int, buffer: *const c_void, len: usize) -> isize;
+    #[cfg(feature = "json_report")]
+    fn getenv(name: *const c_char) -> *const c_char;
     // SAFETY: We are intentionally crashing so no problem.
     // If somehow other code had hooked SIGABRT before main,
     // we cannot really do anything about it.
@@ -11,7 +15,7 @@ unsafe extern "C" {
 }
 
 #[repr(transparent)]
-pub(crate) struct StdErr(());
+pub(crate) struct StdErr(c_int);
 
 /// # Safety
 /// Must be called only once.
@@ -25,17 +29,40 @@ pub(crate) unsafe fn get_stderr() -> StdErr {
     // Therefore, if previous writers wanted to have their output
     // printed, they should have flushed it themselves.
     // We would just use unbuffered `write` calls.
-    StdErr(())
+    StdErr(STDERR_FILENO)
 }
 
-pub(crate) fn print_error(_stderr: &mut StdErr, msg: &str) {
+/// Returns file descriptor selected for JSON report or stderr.
+///
+/// # Safety
+/// Same as for [`get_stderr`].
+/// Also, no other thread should modify environment.
+#[cfg(feature = "json_report")]
+pub(crate) unsafe fn get_json_report_output() -> StdErr {
+    // SAFETY: Name is a valid C string, returned pointer is either null
+    // or points to a valid C string that is not modified by other threads.
+    let fd = unsafe {
+        let value = getenv(crate::json::REPORT_FD_ENV_VAR.as_ptr());
+        if value.is_null() {
+            None
+        } else {
+            crate::json::parse_fd(CStr::from_ptr(value).to_bytes())
+        }
+    };
+    match fd.and_then(|fd| c_int::try_from(fd).ok()) {
+        Some(fd) => StdErr(fd),
+        None => StdErr(STDERR_FILENO),
+    }
+}
+
+pub(crate) fn print_error(stderr: &mut StdErr, msg: &str) {
     let mut rest = msg.as_bytes();
     while !rest.is_empty() {
         // SAFETY: We are trying to output diagnostic info on best effort basis.
         unsafe {
             // While unbuffered write is slow, this code shouldn't run
             // almost never because it is executed only when TypeIds collide.
-            let res = write(STDERR_FILENO, rest.as_ptr().cast(), rest.len());
+            let res = write(stderr.0, rest.as_ptr().cast(), rest.len());
             if res < 0 {
                 // Well, POSIX says that we should handle this and check `errno`.
                 // But `errno` is a bad API that is not standardized for linking.
@@ -4,6 +4,8 @@ use core::ptr;
 #[derive(Clone, Copy)]
 struct Handle(u32);
 
+#[cfg(feature = "json_report")]
+const STD_OUTPUT_HANDLE: u32 = 0xFFFF_FFF5;
 const STD_ERROR_HANDLE: u32 = 0xFFFF_FFF4;
 const PROCESS_TERMINATE_ACCESS: u32 = 1;
 
@@ -17,6 +19,8 @@ unsafe extern "system" {
         bytes_written: *mut u32,
         overlapping: *mut (),
     ) -> i32;
+    #[cfg(feature = "json_report")]
+    fn GetEnvironmentVariableA(name: *const u8, buffer: *mut u8, size: u32) -> u32;
     safe fn GetCurrentProcessId() -> u32;
     fn OpenProcess(desired_acces: u32, inherit_handle: i32, process_id: u32) -> Handle;
     fn TerminateProcess(handle: Handle, exit_code: u32) -> i32;
@@ -32,6 +36,36 @@ pub(crate) unsafe fn get_stderr() -> StdErr {
     StdErr(unsafe { GetStdHandle(STD_ERROR_HANDLE) })
 }
 
+/// Returns handle selected for JSON report or stderr.
+///
+/// # Safety
+/// Same as for [`get_stderr`].
+#[cfg(feature = "json_report")]
+pub(crate) unsafe fn get_json_report_output() -> StdErr {
+    let mut buffer = [0u8; 16];
+    // SAFETY: We follow WinAPI requirements.
+    // Returned length doesn't include terminating zero if value fits into buffer.
+    let len = unsafe {
+        GetEnvironmentVariableA(
+            crate::json::REPORT_FD_ENV_VAR.as_ptr().cast(),
+            buffer.as_mut_ptr(),
+            buffer.len().try_into().unwrap(),
+        )
+    };
+    let value = usize::try_from(len)
+        .ok()
+        .and_then(|len| buffer.get(..len))
+        .and_then(crate::json::parse_fd);
+    // SAFETY: Standard handles are always valid to request.
+    unsafe {
+        match value {
+            None | Some(2) => StdErr(GetStdHandle(STD_ERROR_HANDLE)),
+            Some(1) => StdErr(GetStdHandle(STD_OUTPUT_HANDLE)),
+            Some(handle) => StdErr(Handle(handle)),
+        }
+    }
+}
+
 pub(crate) fn print_error(stderr: &mut StdErr, msg: &str) {
     // SAFETY: Caller correctly acquired stderr so no problem.
     let mut rest = msg.as_bytes();
@@ -0,0 +1,105 @@
+use small_type_id::HasTypeId as _;
+use small_type_id::any::SmallAny;
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+struct A(u32);
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+struct B(&'static str);
+
+#[test]
+fn downcast_references() {
+    let mut a = A(1);
+    let val: &mut dyn SmallAny = &mut a;
+    assert_eq!(val.small_type_id(), A::TYPE_ID);
+    assert!(val.is::<A>());
+    assert!(!val.is::<B>());
+    assert_eq!(val.downcast_ref::<A>(), Some(&A(1)));
+    assert_eq!(val.downcast_ref::<B>(), None);
+    assert_eq!(val.downcast_mut::<B>(), None);
+    val.downcast_mut::<A>().unwrap().0 = 2;
+    assert_eq!(a, A(2));
+}
+
+#[test]
+fn downcast_references_with_auto_traits() {
+    let mut b = B("b");
+    let val: &mut (dyn SmallAny + Send) = &mut b;
+    assert!(val.is::<B>());
+    assert_eq!(val.downcast_ref::<A>(), None);
+    val.downcast_mut::<B>().unwrap().0 = "c";
+    let val: &(dyn SmallAny + Send + Sync) = &b;
+    assert!(!val.is::<A>());
+    assert_eq!(val.downcast_ref::<B>(), Some(&B("c")));
+}
+
+#[test]
+fn debug() {
+    let a = A(1);
+    let expected = format!("SmallAny({:?})", A::TYPE_ID);
+    assert_eq!(format!("{:?}", &a as &dyn SmallAny), expected);
+    assert_eq!(format!("{:?}", &a as &(dyn SmallAny + Send)), expected);
+    assert_eq!(
+        format!("{:?}", &a as &(dyn SmallAny + Send + Sync)),
+        expected
+    );
+}
+
+#[cfg(feature = "alloc")]
+mod pointers {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use small_type_id::any::Downcast as _;
+
+    use super::*;
+
+    #[test]
+    fn boxes() {
+        let val: Box<dyn SmallAny> = Box::new(A(1));
+        let val = val.downcast::<B>().unwrap_err();
+        assert_eq!(val.downcast::<A>().ok(), Some(Box::new(A(1))));
+
+        let val: Box<dyn SmallAny + Send> = Box::new(B("b"));
+        let val = val.downcast::<A>().unwrap_err();
+        assert_eq!(val.downcast::<B>().ok(), Some(Box::new(B("b"))));
+
+        let val: Box<dyn SmallAny + Send + Sync> = Box::new(A(2));
+        assert_eq!(*val.downcast::<A>().ok().unwrap(), A(2));
+    }
+
+    #[test]
+    fn rcs() {
+        let val: Rc<dyn SmallAny> = Rc::new(A(1));
+        let val = val.downcast::<B>().unwrap_err();
+        let copy = val.clone();
+        let val: Rc<A> = val.downcast().ok().unwrap();
+        assert_eq!(Rc::strong_count(&val), 2);
+        drop(copy);
+        assert_eq!(Rc::into_inner(val), Some(A(1)));
+
+        let val: Rc<dyn SmallAny + Send> = Rc::new(B("b"));
+        assert_eq!(*val.downcast::<B>().ok().unwrap(), B("b"));
+        let val: Rc<dyn SmallAny + Send + Sync> = Rc::new(B("b"));
+        assert!(val.downcast::<A>().is_err());
+    }
+
+    #[test]
+    fn arcs() {
+        let val: Arc<dyn SmallAny + Send + Sync> = Arc::new(A(1));
+        let copy = val.clone();
+        std::thread::spawn(move || assert!(copy.is::<A>()))
+            .join()
+            .unwrap();
+        let val = val.downcast::<B>().unwrap_err();
+        assert_eq!(
+            Arc::into_inner(val.downcast::<A>().ok().unwrap()),
+            Some(A(1))
+        );
+
+        let val: Arc<dyn SmallAny> = Arc::new(B("b"));
+        assert_eq!(*val.downcast::<B>().ok().unwrap(), B("b"));
+        let val: Arc<dyn SmallAny + Send> = Arc::new(B("b"));
+        assert!(val.downcast::<A>().is_err());
+    }
+}
@@ -35,6 +35,8 @@ struct r#CheckRawKeyword;
 #[allow(non_camel_case_types)]
 struct r#pub;
 
+small_type_id::test_unique_type_ids!();
+
 #[test]
 fn check_values() {
     let arr = [
@@ -123,3 +125,24 @@ fn check_initial_value() {
         UnionType::TYPE_ID.as_u32()
     );
 }
+
+#[test]
+// MIRI unsupported until https://github.com/rust-lang/miri/issues/450 fixed
+#[cfg(not(miri))]
+fn find_types() {
+    // Concurrent calls must wait for single verification.
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(small_type_id::ensure_verified);
+        }
+    });
+    let found = small_type_id::find_registered_type(EnumType::TYPE_ID);
+    if cfg!(feature = "unsafe_dont_register_types") {
+        assert!(found.is_none());
+        return;
+    }
+    assert!(found.is_some_and(|e| e.type_id == EnumType::TYPE_ID));
+    // SAFETY: Value conforms to invariants of `TypeId`.
+    let unused = unsafe { TypeId::from_bytes(1u32.to_le_bytes()) }.unwrap();
+    assert!(small_type_id::find_registered_type(unused).is_none());
+}
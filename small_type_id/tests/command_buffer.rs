@@ -0,0 +1,150 @@
+#![cfg(feature = "alloc")]
+
+use std::cell::RefCell;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::rc::Rc;
+
+use small_type_id::HasTypeId as _;
+use small_type_id::command_buffer::{CommandBuffer, Handlers};
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+struct Byte(u8);
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+struct Wide(u64, u8);
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+struct Empty;
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+#[repr(align(64))]
+struct Aligned(u8);
+
+#[derive(small_type_id::HasTypeId)]
+struct Counted(#[allow(dead_code)] Rc<()>);
+
+#[test]
+fn iteration_preserves_order_and_alignment() {
+    let mut buffer = CommandBuffer::new();
+    assert!(buffer.is_empty());
+    for i in 0..100 {
+        buffer.push(Byte(i));
+        buffer.push(Empty);
+        buffer.push(Wide(u64::from(i) << 40, i));
+        if i == 50 {
+            buffer.push(Aligned(i));
+        }
+    }
+    assert_eq!(buffer.len(), 301);
+    let mut i = 0;
+    let mut iter = buffer.iter();
+    while let Some((type_id, val)) = iter.next() {
+        assert_eq!(type_id, Byte::TYPE_ID);
+        assert_eq!(val.downcast_ref::<Byte>(), Some(&Byte(i)));
+        assert_eq!(iter.next().unwrap().1.downcast_ref::<Empty>(), Some(&Empty));
+        let (type_id, val) = iter.next().unwrap();
+        assert_eq!(type_id, Wide::TYPE_ID);
+        let wide = val.downcast_ref::<Wide>().unwrap();
+        assert!(std::ptr::from_ref(wide).is_aligned());
+        assert_eq!(wide, &Wide(u64::from(i) << 40, i));
+        if i == 50 {
+            let aligned = iter.next().unwrap().1.downcast_ref::<Aligned>().unwrap();
+            assert!(std::ptr::from_ref(aligned).is_aligned());
+            assert_eq!(aligned, &Aligned(50));
+        }
+        i += 1;
+    }
+    assert_eq!(i, 100);
+}
+
+#[test]
+fn drain_with_handlers() {
+    let mut buffer = CommandBuffer::with_capacity(16);
+    let log = RefCell::new(Vec::new());
+    for round in 0..3 {
+        buffer.push(Byte(round));
+        buffer.push(Wide(7, round));
+        buffer.push(Empty);
+        let mut handlers = Handlers::new();
+        handlers
+            .on(|Byte(x)| log.borrow_mut().push(format!("byte {x}")))
+            .on(|Wide(x, y)| log.borrow_mut().push(format!("wide {x} {y}")));
+        assert!(handlers.handles::<Byte>());
+        assert!(!handlers.handles::<Empty>());
+        let capacity = buffer.capacity();
+        buffer.drain_with(&mut handlers);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.iter().count(), 0);
+        assert_eq!(buffer.capacity(), capacity);
+    }
+    assert_eq!(
+        log.into_inner(),
+        [
+            "byte 0", "wide 7 0", "byte 1", "wide 7 1", "byte 2", "wide 7 2"
+        ]
+    );
+}
+
+#[test]
+fn drops_values() {
+    let rc = Rc::new(());
+    let mut buffer = CommandBuffer::new();
+    buffer.push(Counted(rc.clone()));
+    buffer.push(Counted(rc.clone()));
+    assert_eq!(Rc::strong_count(&rc), 3);
+    buffer.clear();
+    assert_eq!(Rc::strong_count(&rc), 1);
+
+    buffer.push(Counted(rc.clone()));
+    let mut kept = Vec::new();
+    let mut handlers = Handlers::new();
+    handlers.on(|c: Counted| kept.push(c));
+    buffer.drain_with(&mut handlers);
+    drop(handlers);
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(kept);
+
+    buffer.push(Counted(rc.clone()));
+    buffer.push(Byte(1));
+    drop(buffer);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn panic_in_handler_drops_remaining_values() {
+    let rc = Rc::new(());
+    let mut buffer = CommandBuffer::new();
+    buffer.push(Counted(rc.clone()));
+    buffer.push(Byte(1));
+    buffer.push(Counted(rc.clone()));
+    let mut handlers = Handlers::new();
+    handlers.on(|_: Byte| panic!("handler failed"));
+    let res = catch_unwind(AssertUnwindSafe(|| buffer.drain_with(&mut handlers)));
+    assert!(res.is_err());
+    assert!(buffer.is_empty());
+    assert_eq!(Rc::strong_count(&rc), 1);
+    buffer.push(Byte(2));
+    assert_eq!(buffer.len(), 1);
+}
+
+#[test]
+fn debug() {
+    let mut buffer = CommandBuffer::new();
+    buffer.push(Byte(1));
+    buffer.push(Empty);
+    assert_eq!(
+        format!("{:?}", buffer),
+        format!("[{:?}, {:?}]", Byte::TYPE_ID, Empty::TYPE_ID)
+    );
+}
+
+#[test]
+fn zero_capacity() {
+    let mut buffer = CommandBuffer::with_capacity(0);
+    assert_eq!(buffer.capacity(), 0);
+    assert!(buffer.is_empty());
+    buffer.push(Empty);
+    buffer.push(Byte(3));
+    assert_eq!(buffer.len(), 2);
+    assert!(buffer.capacity() > 0);
+}
@@ -0,0 +1,79 @@
+use small_type_id::dispatch::DispatchTable;
+use small_type_id::{HasTypeId as _, TypeId};
+
+#[derive(small_type_id::HasTypeId)]
+struct A;
+
+#[derive(small_type_id::HasTypeId)]
+struct B;
+
+#[derive(small_type_id::HasTypeId)]
+struct C;
+
+#[derive(small_type_id::HasTypeId)]
+struct Missing;
+
+static NAMES: DispatchTable<&str, 3> = small_type_id::dispatch_table! {
+    A => "A",
+    B => "B",
+    C => "C",
+};
+
+#[test]
+fn get() {
+    assert_eq!(NAMES.len(), 3);
+    assert_eq!(NAMES.get(A::TYPE_ID), Some(&"A"));
+    assert_eq!(NAMES.get(B::TYPE_ID), Some(&"B"));
+    assert_eq!(NAMES.get(C::TYPE_ID), Some(&"C"));
+    assert_eq!(NAMES.get(Missing::TYPE_ID), None);
+    assert!(!NAMES.contains(Missing::TYPE_ID));
+    const { assert!(NAMES.contains(B::TYPE_ID)) };
+}
+
+#[test]
+fn empty() {
+    const EMPTY: DispatchTable<u8, 0> = small_type_id::dispatch_table! {};
+    assert!(EMPTY.is_empty());
+    assert_eq!(EMPTY.get(A::TYPE_ID), None);
+    assert_eq!(format!("{EMPTY:?}"), "{}");
+}
+
+#[test]
+fn non_copy_values() {
+    let table = small_type_id::dispatch_table! {
+        A => String::from("a"),
+        C => String::from("c"),
+    };
+    assert_eq!(table.get(A::TYPE_ID).map(String::as_str), Some("a"));
+    assert_eq!(table.get(C::TYPE_ID).map(String::as_str), Some("c"));
+    assert_eq!(table.iter().count(), 2);
+}
+
+const fn entries<const N: usize>(first: u32, step: u32) -> [(TypeId, u32); N] {
+    let mut res = [(A::TYPE_ID, 0); N];
+    let mut i = 0;
+    let mut val = first;
+    while i < N {
+        res[i] = match TypeId::from_u32(val) {
+            Ok(x) => (x, val),
+            Err(_) => panic!("Invalid TypeId"),
+        };
+        i += 1;
+        val += step;
+    }
+    res
+}
+
+#[test]
+fn many_ids() {
+    static CONSECUTIVE: DispatchTable<u32, 300> = DispatchTable::new(entries(1, 1));
+    static SPREAD: DispatchTable<u32, 300> = DispatchTable::new(entries(12_345, 7_000_001));
+    for table in [&CONSECUTIVE, &SPREAD] {
+        for (type_id, &val) in table.iter() {
+            assert_eq!(type_id.as_u32(), val);
+            assert_eq!(table.get(type_id), Some(&val));
+        }
+    }
+    assert_eq!(CONSECUTIVE.get(TypeId::from_u32(301).unwrap()), None);
+    assert_eq!(SPREAD.get(TypeId::from_u32(12_346).unwrap()), None);
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "alloc")]
+
+use small_type_id::hash::{TypeIdMap, TypeIdSet};
+use small_type_id::{HasTypeId as _, TypeId};
+
+#[derive(small_type_id::HasTypeId)]
+struct A;
+
+#[derive(small_type_id::HasTypeId)]
+struct B;
+
+#[test]
+fn map() {
+    let mut map = TypeIdMap::default();
+    map.insert(A::TYPE_ID, "A");
+    map.insert(B::TYPE_ID, "B");
+    assert_eq!(map.get(&A::TYPE_ID), Some(&"A"));
+    assert_eq!(map.get(&B::TYPE_ID), Some(&"B"));
+    assert_eq!(map.remove(&A::TYPE_ID), Some("A"));
+    assert_eq!(map.get(&A::TYPE_ID), None);
+}
+
+#[test]
+fn many_ids() {
+    let ids = (1..10_000).map(|x| TypeId::from_u32(x * 7919).unwrap());
+    let set: TypeIdSet = ids.clone().collect();
+    assert_eq!(set.len(), 9_999);
+    assert!(ids.clone().all(|x| set.contains(&x)));
+    assert!(!set.contains(&TypeId::from_u32(7918).unwrap()));
+}
@@ -0,0 +1,103 @@
+// Only this target invokes `registry!` so other tests and doctests don't depend on it.
+// Run it with `cargo test -p small_type_id --features=manual_registry --lib --test manual_registry`.
+#![cfg(feature = "manual_registry")]
+
+use std::collections::HashSet;
+
+use small_type_id::any::SmallAny;
+use small_type_id::{HasTypeId, TypeId};
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+struct A(u32);
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+struct B(&'static str);
+
+mod some_module {
+    #[derive(small_type_id::HasTypeId)]
+    pub struct A;
+}
+
+small_type_id::registry! { A, B, some_module::A }
+
+small_type_id::test_unique_type_ids!();
+
+#[test]
+fn iter_types() {
+    if cfg!(feature = "unsafe_dont_register_types") {
+        assert_eq!(small_type_id::iter_registered_types().count(), 0);
+        return;
+    }
+    let set: HashSet<TypeId> = small_type_id::iter_registered_types()
+        .map(|x| x.type_id)
+        .collect();
+    let etalon: HashSet<TypeId> = [A::TYPE_ID, B::TYPE_ID, some_module::A::TYPE_ID]
+        .into_iter()
+        .collect();
+    assert_eq!(set, etalon);
+}
+
+#[cfg(feature = "debug_type_name")]
+#[test]
+fn test_id_to_name() {
+    use std::collections::HashMap;
+
+    #[rustfmt::skip]
+    let key_to_name: HashMap<TypeId, &str> = [
+            (A::TYPE_ID,              "manual_registry::A"),
+            (B::TYPE_ID,              "manual_registry::B"),
+            (some_module::A::TYPE_ID, "manual_registry::some_module::A"),
+        ]
+        .into_iter()
+        .collect();
+    for entry in small_type_id::iter_registered_types() {
+        assert_eq!(entry.debug_type_name, key_to_name[&entry.type_id]);
+    }
+}
+
+#[test]
+fn find_types() {
+    // Concurrent calls must wait for single verification.
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(small_type_id::ensure_verified);
+        }
+    });
+    let found = small_type_id::find_registered_type(B::TYPE_ID);
+    if cfg!(feature = "unsafe_dont_register_types") {
+        assert!(found.is_none());
+        return;
+    }
+    assert!(found.is_some_and(|e| e.type_id == B::TYPE_ID));
+    // SAFETY: Value conforms to invariants of `TypeId`.
+    let unused = unsafe { TypeId::from_bytes(1u32.to_le_bytes()) }.unwrap();
+    assert!(small_type_id::find_registered_type(unused).is_none());
+}
+
+#[test]
+fn downcast_after_verification() {
+    let mut a = A(1);
+    let val: &mut dyn SmallAny = &mut a;
+    assert_eq!(val.downcast_ref::<B>(), None);
+    val.downcast_mut::<A>().unwrap().0 = 2;
+    assert_eq!(a, A(2));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn type_map() {
+    let mut map = small_type_id::type_map::TypeMap::new();
+    assert_eq!(map.insert(A(1)), None);
+    assert_eq!(map.insert(B("b")), None);
+    assert_eq!(map.get::<A>(), Some(&A(1)));
+    assert_eq!(map.remove::<B>(), Some(B("b")));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn heapless_type_map() {
+    let mut map = small_type_id::type_map::HeaplessTypeMap::<2>::new();
+    assert_eq!(map.insert(A(1)), Ok(None));
+    assert_eq!(map.get::<A>(), Some(&A(1)));
+    assert_eq!(map.remove::<A>(), Some(A(1)));
+}
@@ -0,0 +1,63 @@
+use small_type_id::{HasTypeId as _, TypeId};
+
+#[derive(small_type_id::HasTypeId)]
+struct A;
+
+#[derive(small_type_id::HasTypeId)]
+struct B;
+
+#[derive(small_type_id::HasTypeId)]
+struct C;
+
+#[derive(small_type_id::HasTypeId)]
+struct D;
+
+mod nested {
+    #[derive(small_type_id::HasTypeId)]
+    pub struct E;
+}
+
+fn classify(id: TypeId) -> &'static str {
+    small_type_id::match_type!(id, {
+        A => "a",
+        B | C => "b or c",
+        nested::E => "e",
+        _ => "other",
+    })
+}
+
+#[test]
+fn arms() {
+    assert_eq!(classify(A::TYPE_ID), "a");
+    assert_eq!(classify(B::TYPE_ID), "b or c");
+    assert_eq!(classify(C::TYPE_ID), "b or c");
+    assert_eq!(classify(nested::E::TYPE_ID), "e");
+    assert_eq!(classify(D::TYPE_ID), "other");
+}
+
+#[test]
+fn only_default() {
+    let res = small_type_id::match_type!(A::TYPE_ID, { _ => 1 });
+    assert_eq!(res, 1);
+}
+
+#[test]
+fn const_context() {
+    const fn is_a(id: TypeId) -> bool {
+        small_type_id::match_type!(id, { A => true, _ => false })
+    }
+    const { assert!(is_a(A::TYPE_ID)) };
+    const { assert!(!is_a(B::TYPE_ID)) };
+}
+
+#[test]
+fn block_bodies() {
+    let value = |id| small_type_id::match_type!(id, { A => { 1 } B => { 2 } _ => 0 });
+    assert_eq!(value(A::TYPE_ID), 1);
+    assert_eq!(value(B::TYPE_ID), 2);
+    assert_eq!(value(C::TYPE_ID), 0);
+
+    let value = |id| small_type_id::match_type!(id, { A => { 1 }, B | C => { 2 } _ => { 0 } });
+    assert_eq!(value(C::TYPE_ID), 2);
+    assert_eq!(value(D::TYPE_ID), 0);
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "serde")]
+
+use serde_test::{Configure as _, Token, assert_de_tokens_error, assert_tokens};
+use small_type_id::{HasTypeId as _, TypeId};
+
+#[derive(small_type_id::HasTypeId)]
+struct A;
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Registered {
+    #[serde(with = "small_type_id::serde::registered")]
+    type_id: TypeId,
+}
+
+#[test]
+fn readable_uses_hex() {
+    let type_id = TypeId::from_u32(0xABC).unwrap();
+    assert_tokens(&type_id.readable(), &[Token::Str("ABC")]);
+    let type_id = TypeId::from_u32(0x7FFF_FFFF).unwrap();
+    assert_tokens(&type_id.readable(), &[Token::Str("7FFFFFFF")]);
+}
+
+#[test]
+fn compact_uses_u32() {
+    let type_id = TypeId::from_u32(0xABC).unwrap();
+    assert_tokens(&type_id.compact(), &[Token::U32(0xABC)]);
+}
+
+#[test]
+fn accepts_other_representations() {
+    let type_id = TypeId::from_u32(0xABC).unwrap();
+    serde_test::assert_de_tokens(&type_id.readable(), &[Token::Str("0xabc")]);
+    serde_test::assert_de_tokens(&type_id.readable(), &[Token::U64(0xABC)]);
+    serde_test::assert_de_tokens(&type_id.compact(), &[Token::I32(0xABC)]);
+}
+
+#[test]
+fn rejects_invalid_values() {
+    assert_de_tokens_error::<serde_test::Readable<TypeId>>(
+        &[Token::Str("0")],
+        r#"invalid value: string "0", expected valid TypeId (TypeId cannot be zero)"#,
+    );
+    assert_de_tokens_error::<serde_test::Readable<TypeId>>(
+        &[Token::Str("hello")],
+        r#"invalid value: string "hello", expected TypeId as hexadecimal string or u32"#,
+    );
+    assert_de_tokens_error::<serde_test::Compact<TypeId>>(
+        &[Token::U32(0)],
+        "invalid value: integer `0`, expected valid TypeId (TypeId cannot be zero)",
+    );
+    assert_de_tokens_error::<serde_test::Compact<TypeId>>(
+        &[Token::U32(0x8000_0001)],
+        "invalid value: integer `2147483649`, expected valid TypeId (most significant bit of TypeId must be zero)",
+    );
+    assert_de_tokens_error::<serde_test::Compact<TypeId>>(
+        &[Token::U64(1 << 32)],
+        "invalid value: integer `4294967296`, expected TypeId as hexadecimal string or u32",
+    );
+    assert_de_tokens_error::<serde_test::Compact<TypeId>>(
+        &[Token::I32(-1)],
+        "invalid value: integer `-1`, expected TypeId as hexadecimal string or u32",
+    );
+}
+
+// MIRI unsupported until https://github.com/rust-lang/miri/issues/450 fixed
+#[test]
+#[cfg(not(miri))]
+#[cfg(not(feature = "unsafe_dont_register_types"))]
+fn registered_mode() {
+    let value = Registered {
+        type_id: A::TYPE_ID,
+    };
+    assert_tokens(
+        &value.compact(),
+        &[
+            Token::Struct {
+                name: "Registered",
+                len: 1,
+            },
+            Token::Str("type_id"),
+            Token::U32(A::TYPE_ID.as_u32()),
+            Token::StructEnd,
+        ],
+    );
+
+    let unknown = (1..)
+        .map(|x: u32| TypeId::from_u32(x).unwrap())
+        .find(|&x| small_type_id::find_registered_type(x).is_none())
+        .unwrap();
+    assert_de_tokens_error::<serde_test::Compact<Registered>>(
+        &[
+            Token::Struct {
+                name: "Registered",
+                len: 1,
+            },
+            Token::Str("type_id"),
+            Token::U32(unknown.as_u32()),
+            Token::StructEnd,
+        ],
+        "TypeId doesn't belong to any registered type",
+    );
+}
+
+// MIRI unsupported until https://github.com/rust-lang/miri/issues/450 fixed
+#[test]
+#[cfg(not(miri))]
+#[cfg(not(feature = "unsafe_dont_register_types"))]
+fn type_entry() {
+    let entry = small_type_id::find_registered_type(A::TYPE_ID).unwrap();
+    #[cfg(not(feature = "debug_type_name"))]
+    serde_test::assert_ser_tokens(
+        &entry.readable(),
+        &[
+            Token::Struct {
+                name: "TypeEntry",
+                len: 1,
+            },
+            Token::Str("type_id"),
+            Token::Str(Box::leak(A::TYPE_ID.to_string().into_boxed_str())),
+            Token::StructEnd,
+        ],
+    );
+    #[cfg(feature = "debug_type_name")]
+    serde_test::assert_ser_tokens(
+        &entry.compact(),
+        &[
+            Token::Struct {
+                name: "TypeEntry",
+                len: 4,
+            },
+            Token::Str("type_id"),
+            Token::U32(A::TYPE_ID.as_u32()),
+            Token::Str("debug_type_name"),
+            Token::Str("serde::A"),
+            Token::Str("debug_crate_version"),
+            Token::Str(env!("CARGO_PKG_VERSION")),
+            Token::Str("debug_seed"),
+            Token::U32(0),
+            Token::StructEnd,
+        ],
+    );
+}
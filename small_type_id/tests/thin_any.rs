@@ -0,0 +1,97 @@
+#![cfg(feature = "alloc")]
+
+use std::rc::Rc;
+
+use small_type_id::HasTypeId as _;
+use small_type_id::any::{ThinAny, ThinAnySend};
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+struct A(u32);
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+struct Empty;
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+#[repr(align(64))]
+struct Aligned(u8);
+
+#[derive(small_type_id::HasTypeId)]
+struct Counted(#[allow(dead_code)] Rc<()>);
+
+#[test]
+fn size() {
+    assert_eq!(size_of::<ThinAny>(), size_of::<usize>());
+    assert_eq!(size_of::<Option<ThinAny>>(), size_of::<usize>());
+}
+
+#[test]
+fn downcast() {
+    let mut val = ThinAny::new(A(1));
+    assert_eq!(val.type_id(), A::TYPE_ID);
+    assert!(val.is::<A>());
+    assert!(!val.is::<Empty>());
+    assert_eq!(val.downcast_ref::<Empty>(), None);
+    assert_eq!(val.downcast_mut::<Empty>(), None);
+    val.downcast_mut::<A>().unwrap().0 = 2;
+    assert_eq!(val.downcast_ref::<A>(), Some(&A(2)));
+    let val = val.downcast::<Empty>().unwrap_err();
+    assert_eq!(val.downcast::<A>().unwrap(), A(2));
+}
+
+#[test]
+fn zero_sized_and_aligned() {
+    let val = ThinAny::new(Empty);
+    assert_eq!(val.downcast_ref::<Empty>(), Some(&Empty));
+    assert_eq!(val.downcast::<Empty>().unwrap(), Empty);
+
+    let mut val = ThinAny::new(Aligned(3));
+    let ptr: *const Aligned = val.downcast_ref::<Aligned>().unwrap();
+    assert!(ptr.is_aligned());
+    val.downcast_mut::<Aligned>().unwrap().0 = 4;
+    assert_eq!(val.downcast::<Aligned>().unwrap(), Aligned(4));
+}
+
+#[test]
+fn drops_value() {
+    let rc = Rc::new(());
+    let val = ThinAny::new(Counted(rc.clone()));
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(val);
+    assert_eq!(Rc::strong_count(&rc), 1);
+
+    let val = ThinAny::new(Counted(rc.clone()));
+    let val = val.downcast::<A>().unwrap_err();
+    let counted = val.downcast::<Counted>().ok().unwrap();
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(counted);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn debug() {
+    let val = ThinAny::new(A(1));
+    assert_eq!(format!("{:?}", val), format!("ThinAny({:?})", A::TYPE_ID));
+}
+
+#[test]
+fn send_between_threads() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let sender = std::thread::spawn(move || {
+        tx.send(ThinAnySend::new(A(1))).unwrap();
+        tx.send(ThinAnySend::new(Empty)).unwrap();
+    });
+    let mut first = rx.recv().unwrap();
+    assert_eq!(first.type_id(), A::TYPE_ID);
+    assert!(first.is::<A>());
+    first.downcast_mut::<A>().unwrap().0 = 2;
+    assert_eq!(first.downcast_ref::<A>(), Some(&A(2)));
+    assert_eq!(first.downcast::<A>().unwrap(), A(2));
+    let second = rx.recv().unwrap().downcast::<A>().unwrap_err();
+    assert_eq!(
+        format!("{second:?}"),
+        format!("ThinAnySend({:?})", Empty::TYPE_ID)
+    );
+    let second = ThinAny::from(second);
+    assert_eq!(second.downcast::<Empty>().unwrap(), Empty);
+    sender.join().unwrap();
+}
@@ -1,4 +1,4 @@
-use small_type_id::{HasTypeId, TypeId};
+use small_type_id::{ErrorInvalidTypeId, HasTypeId, TypeId};
 
 #[derive(small_type_id::HasTypeId)]
 struct A;
@@ -25,3 +25,185 @@ fn as_usize() {
     assert_ne!(A::TYPE_ID.as_u32() as usize, B::TYPE_ID.as_usize());
     assert_ne!(A::TYPE_ID.as_u32(), B::TYPE_ID.as_u32());
 }
+
+#[test]
+fn safe_constructors() {
+    for type_id in [A::TYPE_ID, B::TYPE_ID] {
+        assert_eq!(TypeId::from_u32(type_id.as_u32()), Ok(type_id));
+        assert_eq!(TypeId::try_from(type_id.as_u32()), Ok(type_id));
+        assert_eq!(TypeId::try_from(type_id.to_bytes()), Ok(type_id));
+        assert_eq!(TypeId::from_le_bytes(type_id.to_bytes()), Ok(type_id));
+        assert_eq!(TypeId::from_be_bytes(type_id.to_be_bytes()), Ok(type_id));
+        assert_eq!(TypeId::from_ne_bytes(type_id.to_ne_bytes()), Ok(type_id));
+    }
+    assert_eq!(
+        TypeId::from_u32(u32::MAX >> 1).map(TypeId::as_u32),
+        Ok(u32::MAX >> 1)
+    );
+}
+
+#[test]
+fn safe_constructors_errors() {
+    assert_eq!(TypeId::from_u32(0), Err(ErrorInvalidTypeId::Zero));
+    assert_eq!(TypeId::try_from([0; 4]), Err(ErrorInvalidTypeId::Zero));
+    assert_eq!(
+        TypeId::from_u32(1 << 31),
+        Err(ErrorInvalidTypeId::MostSignificantBitSet)
+    );
+    assert_eq!(
+        TypeId::from_le_bytes([1, 0, 0, 0x80]),
+        Err(ErrorInvalidTypeId::MostSignificantBitSet)
+    );
+    assert_eq!(
+        TypeId::from_be_bytes([0x80, 0, 0, 1]),
+        Err(ErrorInvalidTypeId::MostSignificantBitSet)
+    );
+    assert_eq!(
+        TypeId::from_registered_bytes(u32::MAX.to_le_bytes()),
+        Err(ErrorInvalidTypeId::MostSignificantBitSet)
+    );
+}
+
+// MIRI unsupported until https://github.com/rust-lang/miri/issues/450 fixed
+#[test]
+#[cfg(not(miri))]
+#[cfg(not(feature = "unsafe_dont_register_types"))]
+fn from_registered_bytes() {
+    assert_eq!(
+        TypeId::from_registered_bytes(A::TYPE_ID.to_bytes()),
+        Ok(A::TYPE_ID)
+    );
+    assert_eq!(
+        TypeId::from_registered_bytes(B::TYPE_ID.to_bytes()),
+        Ok(B::TYPE_ID)
+    );
+    let unknown = (1..)
+        .map(|x: u32| TypeId::from_u32(x).unwrap())
+        .find(|&x| {
+            x != A::TYPE_ID && x != B::TYPE_ID && small_type_id::find_registered_type(x).is_none()
+        })
+        .unwrap();
+    assert_eq!(
+        TypeId::from_registered_bytes(unknown.to_bytes()),
+        Err(ErrorInvalidTypeId::Unregistered)
+    );
+}
+
+#[test]
+fn invalid_type_id_display() {
+    assert_eq!(
+        ErrorInvalidTypeId::Zero.to_string(),
+        "TypeId cannot be zero"
+    );
+    assert_eq!(
+        ErrorInvalidTypeId::MostSignificantBitSet.to_string(),
+        "most significant bit of TypeId must be zero"
+    );
+    assert_eq!(
+        ErrorInvalidTypeId::Unregistered.to_string(),
+        "TypeId doesn't belong to any registered type"
+    );
+}
+
+#[test]
+fn formatting_flags() {
+    let type_id = TypeId::from_u32(0xABC).unwrap();
+    assert_eq!(format!("{}", type_id), "ABC");
+    assert_eq!(format!("{:?}", type_id), "ABC");
+    assert_eq!(format!("{:X}", type_id), "ABC");
+    assert_eq!(format!("{:x}", type_id), "abc");
+    assert_eq!(format!("{:08X}", type_id), "00000ABC");
+    assert_eq!(format!("{:08x}", type_id), "00000abc");
+    assert_eq!(format!("{:#x}", type_id), "0xabc");
+    assert_eq!(format!("{:#010X}", type_id), "0x00000ABC");
+    assert_eq!(format!("{:>6}", type_id), "   ABC");
+    assert_eq!(format!("{:*<6x}", type_id), "abc***");
+    assert_eq!(format!("{:^7}", type_id), "  ABC  ");
+    assert_eq!(format!("{:b}", type_id), "101010111100");
+    assert_eq!(format!("{:#o}", type_id), "0o5274");
+    assert_eq!(format!("{:016b}", type_id), "0000101010111100");
+    for type_id in [A::TYPE_ID, B::TYPE_ID] {
+        let val = type_id.as_u32();
+        assert_eq!(format!("{:X}", type_id), format!("{:X}", val));
+        assert_eq!(format!("{:#010x}", type_id), format!("{:#010x}", val));
+        assert_eq!(format!("{:b}", type_id), format!("{:b}", val));
+        assert_eq!(format!("{:o}", type_id), format!("{:o}", val));
+    }
+}
+
+#[test]
+fn parse_round_trip() {
+    for type_id in [A::TYPE_ID, B::TYPE_ID] {
+        assert_eq!(type_id.to_string().parse(), Ok(type_id));
+        assert_eq!(format!("{:?}", type_id).parse(), Ok(type_id));
+        assert_eq!(format!("{:x}", type_id).parse(), Ok(type_id));
+        assert_eq!(format!("{:#x}", type_id).parse(), Ok(type_id));
+        assert_eq!(format!("{:#010X}", type_id).parse(), Ok(type_id));
+        assert_eq!(format!("{:016}", type_id).parse(), Ok(type_id));
+    }
+}
+
+#[test]
+fn parse_errors() {
+    use small_type_id::ErrorParseTypeId;
+
+    for s in [
+        "",
+        "0x",
+        "+1",
+        "-1",
+        " 1",
+        "1 ",
+        "0X1",
+        "G",
+        "123456789",
+        "0x0x1",
+    ] {
+        assert_eq!(
+            s.parse::<TypeId>(),
+            Err(ErrorParseTypeId::InvalidFormat),
+            "{:?}",
+            s
+        );
+    }
+    assert_eq!(
+        "0".parse::<TypeId>(),
+        Err(ErrorParseTypeId::InvalidValue(ErrorInvalidTypeId::Zero))
+    );
+    assert_eq!(
+        "80000000".parse::<TypeId>(),
+        Err(ErrorParseTypeId::InvalidValue(
+            ErrorInvalidTypeId::MostSignificantBitSet
+        ))
+    );
+}
+
+#[test]
+fn of_and_of_val() {
+    assert_eq!(TypeId::of::<A>(), A::TYPE_ID);
+    assert_eq!(TypeId::of::<B>(), B::TYPE_ID);
+    assert_eq!(TypeId::of_val(&A), A::TYPE_ID);
+    assert_eq!(TypeId::of_val(&B), B::TYPE_ID);
+    const { assert!(TypeId::of::<A>().as_u32() == A::TYPE_ID.as_u32()) };
+}
+
+#[test]
+fn const_comparison() {
+    use core::cmp::Ordering;
+
+    const { assert!(TypeId::of::<A>().eq(&A::TYPE_ID)) };
+    const { assert!(!TypeId::of::<A>().eq(&B::TYPE_ID)) };
+    for (x, y) in [
+        (A::TYPE_ID, A::TYPE_ID),
+        (A::TYPE_ID, B::TYPE_ID),
+        (B::TYPE_ID, A::TYPE_ID),
+    ] {
+        assert_eq!(TypeId::eq(&x, &y), x == y);
+        assert_eq!(TypeId::cmp(&x, &y), Ord::cmp(&x, &y));
+    }
+    let small = TypeId::from_u32(1).unwrap();
+    let big = TypeId::from_u32(0x7FFF_FFFF).unwrap();
+    assert_eq!(small.cmp(&big), Ordering::Less);
+    assert_eq!(big.cmp(&small), Ordering::Greater);
+    assert_eq!(big.cmp(&big), Ordering::Equal);
+}
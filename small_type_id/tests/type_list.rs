@@ -0,0 +1,105 @@
+use small_type_id::HasTypeId as _;
+use small_type_id::konst;
+use small_type_id::type_list::TypeList;
+
+#[derive(small_type_id::HasTypeId)]
+struct A;
+
+#[derive(small_type_id::HasTypeId)]
+struct B;
+
+#[derive(small_type_id::HasTypeId)]
+struct C;
+
+#[derive(small_type_id::HasTypeId)]
+struct D;
+
+small_type_id::define_type_list! {
+    type Abc = (A, B, C);
+}
+small_type_id::define_type_list! {
+    type Cab = (C, A, B,);
+}
+
+#[test]
+fn ids() {
+    assert_eq!(Abc::LEN, 3);
+    assert_eq!(Abc::IDS, [A::TYPE_ID, B::TYPE_ID, C::TYPE_ID]);
+    assert_eq!(Cab::IDS, [C::TYPE_ID, A::TYPE_ID, B::TYPE_ID]);
+    let mut sorted = Abc::IDS;
+    sorted.sort();
+    assert_eq!(Abc::SORTED, sorted);
+}
+
+#[test]
+fn index_of() {
+    assert_eq!(Abc::index_of::<A>(), Some(0));
+    assert_eq!(Abc::index_of::<C>(), Some(2));
+    assert_eq!(Cab::index_of::<C>(), Some(0));
+    assert_eq!(Abc::index_of::<D>(), None);
+    assert!(Cab::contains::<B>());
+    assert!(!Cab::contains::<D>());
+    const { assert!(matches!(Cab::index_of::<B>(), Some(2))) };
+}
+
+#[test]
+fn canonical() {
+    small_type_id::define_type_list! {
+        type Abd = (A, B, D);
+    }
+    small_type_id::define_type_list! {
+        type Ab = (A, B);
+    }
+    const { assert!(konst::eq(&Abc::SORTED, &Cab::SORTED)) };
+    const { assert!(!konst::eq(&Abc::IDS, &Cab::IDS)) };
+    const { assert!(!konst::eq(&Abc::SORTED, &Abd::SORTED)) };
+    const { assert!(!konst::eq(&Abc::SORTED, &Ab::SORTED)) };
+}
+
+mod many {
+    macro_rules! declare {
+        ($($tname:ident),*) => {
+            $(
+                #[derive(small_type_id::HasTypeId)]
+                pub struct $tname;
+            )*
+
+            small_type_id::define_type_list! {
+                pub type All = ($($tname),*);
+            }
+        };
+    }
+
+    declare!(
+        T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15
+    );
+}
+
+#[test]
+fn sizes() {
+    small_type_id::define_type_list! {
+        type Empty = ();
+    }
+    assert_eq!(Empty::IDS, []);
+    assert_eq!(Empty::index_of::<A>(), None);
+
+    assert_eq!(many::All::LEN, 16);
+    assert_eq!(many::All::index_of::<many::T0>(), Some(0));
+    assert_eq!(many::All::index_of::<many::T15>(), Some(15));
+    assert_eq!(many::All::index_of::<A>(), None);
+
+    type Explicit = TypeList<(A, B, C, D), 4>;
+    assert_eq!(Explicit::index_of::<D>(), Some(3));
+}
+
+// Deprecated macro is kept working.
+#[allow(deprecated)]
+#[test]
+fn debug() {
+    let list = <small_type_id::TypeList!(A, B)>::default();
+    assert_eq!(
+        format!("{list:?}"),
+        format!("[{:?}, {:?}]", A::TYPE_ID, B::TYPE_ID)
+    );
+    assert_eq!(format!("{:?}", <small_type_id::TypeList!()>::new()), "[]");
+}
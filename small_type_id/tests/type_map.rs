@@ -0,0 +1,192 @@
+#![cfg(any(feature = "alloc", feature = "heapless"))]
+
+use small_type_id::HasTypeId as _;
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq, Default)]
+struct A(u32);
+
+#[derive(small_type_id::HasTypeId, Debug, PartialEq)]
+struct B(&'static str);
+
+#[cfg(feature = "alloc")]
+mod owned {
+    use std::rc::Rc;
+
+    use small_type_id::type_map::{Entry, TypeMap};
+
+    use super::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = TypeMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert(A(1)), None);
+        assert_eq!(map.insert(B("b")), None);
+        assert_eq!(map.insert(A(2)), Some(A(1)));
+        assert_eq!(map.len(), 2);
+        assert!(map.contains::<A>());
+        assert_eq!(map.get::<A>(), Some(&A(2)));
+        map.get_mut::<B>().unwrap().0 = "c";
+        assert_eq!(map.get::<B>(), Some(&B("c")));
+        assert_eq!(map.remove::<A>(), Some(A(2)));
+        assert_eq!(map.remove::<A>(), None);
+        assert_eq!(map.get::<A>(), None);
+        assert!(!map.contains::<A>());
+        map.clear();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn entry() {
+        let mut map = TypeMap::with_capacity(2);
+        assert!(matches!(map.entry::<A>(), Entry::Vacant(_)));
+        map.entry::<A>().or_default().0 += 1;
+        map.entry::<A>().and_modify(|a| a.0 += 10).or_insert(A(100));
+        assert_eq!(map.get::<A>(), Some(&A(11)));
+        assert_eq!(*map.entry().or_insert(B("b")), B("b"));
+        match map.entry::<B>() {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.get(), &B("b"));
+                assert_eq!(e.insert(B("c")), B("b"));
+                assert_eq!(e.remove(), B("c"));
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        assert!(!map.contains::<B>());
+        match map.entry::<B>() {
+            Entry::Vacant(e) => e.insert(B("d")).0 = "e",
+            Entry::Occupied(_) => unreachable!(),
+        }
+        assert_eq!(map.get::<B>(), Some(&B("e")));
+    }
+
+    #[test]
+    fn iteration() {
+        let mut map = TypeMap::new();
+        map.insert(A(1));
+        map.insert(B("b"));
+        let mut ids: Vec<_> = map.iter().map(|(id, v)| (id, v.small_type_id())).collect();
+        ids.sort();
+        let mut expected = vec![(A::TYPE_ID, A::TYPE_ID), (B::TYPE_ID, B::TYPE_ID)];
+        expected.sort();
+        assert_eq!(ids, expected);
+        for (_, v) in map.iter_mut() {
+            if let Some(a) = v.downcast_mut::<A>() {
+                a.0 = 5;
+            }
+        }
+        assert_eq!(map.get::<A>(), Some(&A(5)));
+    }
+
+    #[test]
+    fn drops_values() {
+        #[derive(small_type_id::HasTypeId)]
+        struct Counted(#[allow(dead_code)] Rc<()>);
+
+        let rc = Rc::new(());
+        let mut map = TypeMap::new();
+        map.insert(Counted(rc.clone()));
+        assert!(map.insert(Counted(rc.clone())).is_some());
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(map);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
+
+#[cfg(feature = "heapless")]
+mod heapless {
+    use std::rc::Rc;
+
+    use small_type_id::type_map::{HeaplessEntry, HeaplessTypeMap};
+
+    use super::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = HeaplessTypeMap::<2>::new();
+        assert!(map.is_empty());
+        assert_eq!(map.capacity(), 2);
+        assert_eq!(map.insert(A(1)), Ok(None));
+        assert_eq!(map.insert(B("b")), Ok(None));
+        assert_eq!(map.insert(A(2)), Ok(Some(A(1))));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get::<A>(), Some(&A(2)));
+        map.get_mut::<B>().unwrap().0 = "c";
+        assert_eq!(
+            map.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            [A::TYPE_ID, B::TYPE_ID]
+        );
+        assert_eq!(map.remove::<A>(), Some(A(2)));
+        assert_eq!(map.remove::<A>(), None);
+        assert!(map.contains::<B>());
+        assert!(!map.contains::<A>());
+        assert_eq!(map.get::<B>(), Some(&B("c")));
+        map.clear();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn entry() {
+        let mut map = HeaplessTypeMap::<1, [u64; 2]>::new();
+        assert!(matches!(map.entry::<A>(), HeaplessEntry::Vacant(_)));
+        map.entry::<A>().or_default().unwrap().0 += 1;
+        map.entry::<A>()
+            .and_modify(|a| a.0 += 10)
+            .or_insert(A(100))
+            .unwrap();
+        assert_eq!(map.get::<A>(), Some(&A(11)));
+        match map.entry::<A>() {
+            HeaplessEntry::Occupied(mut e) => {
+                assert_eq!(e.get(), &A(11));
+                assert_eq!(e.insert(A(12)), A(11));
+                assert_eq!(e.remove(), A(12));
+            }
+            HeaplessEntry::Vacant(_) => unreachable!(),
+        }
+        match map.entry::<A>() {
+            HeaplessEntry::Vacant(e) => e.insert(A(1)).unwrap().0 = 2,
+            HeaplessEntry::Occupied(_) => unreachable!(),
+        }
+        assert_eq!(map.get::<A>(), Some(&A(2)));
+        assert_eq!(map.entry().or_insert(B("b")), Err(B("b")));
+    }
+
+    #[test]
+    fn iteration() {
+        let mut map = HeaplessTypeMap::<2>::new();
+        map.insert(A(1)).unwrap();
+        map.insert(B("b")).unwrap();
+        for (id, v) in map.iter_mut() {
+            assert_eq!(id, v.small_type_id());
+            if let Some(a) = v.downcast_mut::<A>() {
+                a.0 = 5;
+            }
+        }
+        assert_eq!(map.get::<A>(), Some(&A(5)));
+    }
+
+    #[test]
+    fn full() {
+        let mut map = HeaplessTypeMap::<1>::new();
+        assert_eq!(map.insert(A(1)), Ok(None));
+        assert_eq!(map.insert(B("b")), Err(B("b")));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn drops_values() {
+        #[derive(small_type_id::HasTypeId, Debug)]
+        struct Counted(#[allow(dead_code)] Rc<()>);
+
+        let rc = Rc::new(());
+        let mut map = HeaplessTypeMap::<2>::new();
+        map.insert(Counted(rc.clone())).unwrap();
+        assert!(map.insert(Counted(rc.clone())).unwrap().is_some());
+        map.insert(A(1)).unwrap();
+        assert_eq!(Rc::strong_count(&rc), 2);
+        assert!(map.remove::<A>().is_some());
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(map);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
@@ -0,0 +1,82 @@
+#![cfg(any(feature = "bytemuck", feature = "zerocopy"))]
+
+use small_type_id::{HasTypeId as _, TypeId};
+
+#[derive(small_type_id::HasTypeId)]
+struct A;
+
+#[derive(small_type_id::HasTypeId)]
+struct B;
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck {
+    use ::bytemuck::checked::{self, CheckedCastError};
+
+    use super::*;
+
+    #[test]
+    fn casts_to_bytes() {
+        let ids = [A::TYPE_ID, B::TYPE_ID];
+        let bytes: &[u8] = ::bytemuck::cast_slice(&ids);
+        assert_eq!(&bytes[..4], A::TYPE_ID.to_ne_bytes());
+        assert_eq!(&bytes[4..], B::TYPE_ID.to_ne_bytes());
+    }
+
+    #[test]
+    fn checked_cast_from_bits() {
+        let bits = [A::TYPE_ID.as_u32(), B::TYPE_ID.as_u32()];
+        let ids: &[TypeId] = checked::cast_slice(&bits);
+        assert_eq!(ids, [A::TYPE_ID, B::TYPE_ID]);
+
+        for invalid in [0, 1 << 31, u32::MAX] {
+            let bits = [A::TYPE_ID.as_u32(), invalid];
+            assert_eq!(
+                checked::try_cast_slice::<u32, TypeId>(&bits),
+                Err(CheckedCastError::InvalidBitPattern)
+            );
+            assert_eq!(
+                checked::try_from_bytes::<TypeId>(&invalid.to_ne_bytes()),
+                Err(CheckedCastError::InvalidBitPattern)
+            );
+        }
+    }
+
+    #[test]
+    fn zeroed_option_is_none() {
+        let column: [Option<TypeId>; 3] = ::bytemuck::Zeroable::zeroed();
+        assert_eq!(column, [None; 3]);
+        let mut column = [Some(A::TYPE_ID); 2];
+        ::bytemuck::fill_zeroes(&mut column);
+        assert_eq!(column, [None; 2]);
+    }
+}
+
+#[cfg(feature = "zerocopy")]
+mod zerocopy {
+    use ::zerocopy::{FromBytes as _, IntoBytes as _};
+
+    use super::*;
+
+    #[test]
+    fn as_bytes() {
+        assert_eq!(A::TYPE_ID.as_bytes(), A::TYPE_ID.to_ne_bytes());
+        let ids = [A::TYPE_ID, B::TYPE_ID];
+        let bytes = ids.as_bytes();
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(&bytes[4..], B::TYPE_ID.to_ne_bytes());
+    }
+
+    // `TryFromBytes` is not implemented so bytes are read as `u32`s and checked one by one.
+    #[test]
+    fn reads_ids_as_u32() {
+        let ids = [A::TYPE_ID, B::TYPE_ID];
+        let bits = <[u32]>::ref_from_bytes(ids.as_bytes()).unwrap();
+        let read: Result<Vec<TypeId>, _> = bits.iter().map(|&x| TypeId::from_u32(x)).collect();
+        assert_eq!(read.unwrap(), ids);
+
+        for invalid in [0, 1 << 31, u32::MAX] {
+            assert!(TypeId::from_u32(invalid).is_err());
+        }
+        assert!(TypeId::from_registered_bytes(1u32.to_le_bytes()).is_err());
+    }
+}